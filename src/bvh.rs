@@ -14,7 +14,7 @@ pub struct BvhNode {
 }
 
 impl BvhNode {
-    pub fn from_objects_mut(objects: &mut Vec<Box<dyn Object>>) -> BvhNode {
+    pub fn from_objects_mut(objects: &mut Vec<Arc<dyn Object>>) -> BvhNode {
         let mut bbox = AxisAlignedBoundingBox::EMPTY;
         for obj in objects.iter_mut() {
             bbox = bbox.merge(obj.bounding_box());
@@ -23,19 +23,18 @@ impl BvhNode {
             0 => panic!("object list for BVH must be non-empty"),
             1 => {
                 let o = objects.pop().unwrap();
-                let x: Arc<dyn Object> = Arc::from(o);
-                [x.clone(), x]
+                [o.clone(), o]
             }
             2 => {
                 let o2 = objects.pop().unwrap();
                 let o1 = objects.pop().unwrap();
-                [o1.into(), o2.into()]
+                [o1, o2]
             }
             _ => {
                 let axis = bbox.longest_axis();
                 fn cmp_with_axis(
                     f: impl Fn(AxisAlignedBoundingBox) -> f64,
-                ) -> impl Fn(&Box<dyn Object>, &Box<dyn Object>) -> Ordering {
+                ) -> impl Fn(&Arc<dyn Object>, &Arc<dyn Object>) -> Ordering {
                     move |a, b| {
                         let a = f(a.bounding_box());
                         let b = f(b.bounding_box());
@@ -51,8 +50,8 @@ impl BvhNode {
                 }));
                 let mid = objects.len() / 2;
                 let mut right = objects.split_off(mid);
-                let mut left = objects;
-                let a1: Arc<dyn Object> = Arc::new(BvhNode::from_objects_mut(&mut left));
+                let left = objects;
+                let a1: Arc<dyn Object> = Arc::new(BvhNode::from_objects_mut(left));
                 let a2: Arc<dyn Object> = Arc::new(BvhNode::from_objects_mut(&mut right));
                 [a1, a2]
             }
@@ -63,13 +62,19 @@ impl BvhNode {
 }
 
 impl From<Vec<Box<dyn Object>>> for BvhNode {
-    fn from(mut objects: Vec<Box<dyn Object>>) -> Self {
+    fn from(objects: Vec<Box<dyn Object>>) -> Self {
+        objects.into_iter().map(Arc::from).collect::<Vec<_>>().into()
+    }
+}
+
+impl From<Vec<Arc<dyn Object>>> for BvhNode {
+    fn from(mut objects: Vec<Arc<dyn Object>>) -> Self {
         Self::from_objects_mut(&mut objects)
     }
 }
 
 impl Object for BvhNode {
-    fn hit(&self, r: Ray, mut ray_t: Interval) -> Option<HitRecord> {
+    fn hit(&self, r: Ray, mut ray_t: Interval) -> Option<HitRecord<'_>> {
         if !self.bbox.hit(r, ray_t) {
             return None;
         }
@@ -84,4 +89,12 @@ impl Object for BvhNode {
     fn bounding_box(&self) -> AxisAlignedBoundingBox {
         self.bbox
     }
+    fn children(&self) -> Vec<Arc<dyn Object>> {
+        // a node with a single object has it on both sides
+        if Arc::ptr_eq(&self.left, &self.right) {
+            vec![self.left.clone()]
+        } else {
+            vec![self.left.clone(), self.right.clone()]
+        }
+    }
 }
@@ -1,21 +1,36 @@
+use std::f64::consts::{PI, TAU};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
 use image::ExtendedColorType;
-use rayon::iter::{IndexedParallelIterator, ParallelIterator};
-use rayon::slice::ParallelSliceMut;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
-use crate::color::Color;
+use crate::color::{Color, UNIT_LUMINANCE};
+use crate::environment::Environment;
 use crate::interval::Interval;
+use crate::light::Lights;
 use crate::material::Material;
-use crate::object::{Object, ObjectList};
-use crate::pdf::{MixturePdf, ObjectPdf, Pdf};
+use crate::object::{HitRecord, Object, ObjectList};
 use crate::ray::Ray;
+use crate::spectrum::{Radiance, Rgb, SampledWavelengths, Wavelengths};
 use crate::utils::random_double;
 use crate::vec3::{Point, Vec3};
 
+mod animation;
+mod aperture;
+mod film;
+pub use animation::{CameraPath, Keyframe};
+pub use aperture::{Aperture, ApertureMask};
+pub use film::{Film, Filter};
+
 pub struct CameraBuilder {
     aspect_ratio: f64,
     image_width: u64,
     samples_per_pixel: u64,
     max_depth: u64,
+    /// How samples are weighted into the pixels around them.
+    filter: Filter,
     /// Vertical view angle
     vfov: f64,
     look_from: Point,
@@ -23,7 +38,143 @@ pub struct CameraBuilder {
     vup: Vec3,
     defocus_angle: f64,
     focus_dist: f64,
+    /// Moves the image sideways in the focus plane, as a fraction of its width, like a shift
+    /// lens. This frames things off to the side without turning the camera.
+    shift_x: f64,
+    /// Moves the image up in the focus plane as a fraction of its height, e.g. to fit a tall
+    /// building while keeping the camera level so that its walls stay parallel.
+    shift_y: f64,
+    /// Angle in degrees that the focus plane is turned by around the horizontal line through
+    /// its center, with the top going away from the camera for positive angles. This puts the
+    /// ground in focus from near to far, or fakes a miniature with a narrow band of focus
+    /// across it.
+    tilt: f64,
+    aperture: Aperture,
+    /// How much the lens barrel cuts into the aperture towards the edges of the image, giving
+    /// out of focus highlights there a cat's eye shape and darkening the corners. At 1 the
+    /// aperture is cut in half in the corners.
+    cat_eye: f64,
     background: Color,
+    /// Replaces `background` and is sampled as a light.
+    environment: Option<Arc<dyn Environment>>,
+    /// Trace sampled wavelengths instead of RGB, so that dispersive materials split light.
+    spectral: bool,
+    model: CameraModel,
+    stereo: Option<Stereo>,
+    crop: Option<Crop>,
+    /// Replaces `vfov` and `defocus_angle` and sets the exposure.
+    physical: Option<PhysicalCamera>,
+}
+
+/// The settings of a real camera, which the field of view, depth of field and brightness of the
+/// image follow from. World units are taken to be meters.
+///
+/// The exposure is close to 1 when following the sunny 16 rule, with f/16, ISO 100 and a shutter of
+/// 1/100 s, which suits scenes lit by a [`Sky`](crate::environment::Sky).
+#[derive(Clone, Copy)]
+pub struct PhysicalCamera {
+    /// Focal length of the lens in mm.
+    pub focal_length: f64,
+    /// Focal length divided by the diameter of the aperture.
+    pub f_number: f64,
+    /// Width and height of the sensor in mm. The image is the largest part of the sensor with
+    /// the aspect ratio of the image.
+    pub sensor_size: (f64, f64),
+    pub iso: f64,
+    /// How long the shutter is open in seconds.
+    pub shutter: f64,
+}
+
+impl Default for PhysicalCamera {
+    /// A full frame camera with a 50 mm lens at f/16, ISO 100 and 1/100 s.
+    fn default() -> Self {
+        Self {
+            focal_length: 50.0,
+            f_number: 16.0,
+            sensor_size: (36.0, 24.0),
+            iso: 100.0,
+            shutter: 0.01,
+        }
+    }
+}
+
+impl PhysicalCamera {
+    /// Vertical view angle in degrees for an image with `aspect_ratio`.
+    fn vfov(&self, aspect_ratio: f64) -> f64 {
+        let (width, height) = self.sensor_size;
+        let height = height.min(width / aspect_ratio);
+        2.0 * (height / 2.0 / self.focal_length).atan().to_degrees()
+    }
+
+    /// Radius of the aperture in meters.
+    fn aperture_radius(&self) -> f64 {
+        self.focal_length / self.f_number / 2.0 / 1000.0
+    }
+
+    /// How much radiance is scaled by, from the saturation based exposure of ISO 12232 with
+    /// luminance in cd/m².
+    fn exposure(&self) -> f64 {
+        let saturating_luminance = 78.0 / (0.65 * self.iso) * self.f_number.powi(2) / self.shutter;
+        UNIT_LUMINANCE * 1000.0 / saturating_luminance
+    }
+}
+
+/// How the camera turns positions on the image into rays.
+#[derive(Clone, Copy)]
+pub enum CameraModel {
+    /// A pinhole, or a thin lens with depth of field, that sees `vfov` vertically.
+    Perspective,
+    /// Parallel rays through a view that is `view_height` world units high.
+    Orthographic { view_height: f64 },
+    /// The whole sphere around the camera in latitude-longitude layout, as used by VR viewers.
+    /// The center of the image is straight ahead. Images should be twice as wide as high.
+    Equirectangular,
+    /// A circular image of `fov` degrees across, which can be up to 360.
+    Fisheye { fov: f64, mapping: FisheyeMapping },
+}
+
+/// How the angle from the center of a fisheye image grows with the distance from its center.
+#[derive(Clone, Copy)]
+pub enum FisheyeMapping {
+    /// Proportional to the distance, which keeps angles between things evenly spaced.
+    Equidistant,
+    /// Keeps the solid angle of each pixel the same, like most real fisheye lenses.
+    Equisolid,
+}
+
+/// Where the two eyes of a stereo camera go in the image.
+#[derive(Clone, Copy)]
+pub enum StereoLayout {
+    /// Left eye on the left, right eye on the right, doubling the width of the image.
+    SideBySide,
+    /// Left eye on top, right eye on the bottom, doubling the height of the image.
+    TopBottom,
+}
+
+/// What a camera that only renders part of the image saves.
+#[derive(Clone, Copy)]
+pub enum CropOutput {
+    /// Just the part that was rendered.
+    Cropped,
+    /// The whole image, black outside of the part that was rendered.
+    FullFrame,
+}
+
+/// The part of the image that is rendered, in pixels from its top left corner.
+#[derive(Clone, Copy)]
+struct Crop {
+    x: (u64, u64),
+    y: (u64, u64),
+    output: CropOutput,
+}
+
+#[derive(Clone, Copy)]
+struct Stereo {
+    /// Distance between the eyes.
+    ipd: f64,
+    /// Distance of what appears at the depth of the screen.
+    convergence: f64,
+    layout: StereoLayout,
 }
 
 macro_rules! builder_methods {
@@ -37,6 +188,12 @@ macro_rules! builder_methods {
     };
 }
 
+impl Default for CameraBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CameraBuilder {
     pub const fn new() -> Self {
         Self {
@@ -44,13 +201,25 @@ impl CameraBuilder {
             image_width: 100,
             samples_per_pixel: 10,
             max_depth: 10,
+            filter: Filter::Box { radius: 0.5 },
             vfov: 90.0,
             look_from: Point::new(0.0, 0.0, 0.0),
             look_at: Point::new(0.0, 0.0, -1.0),
             vup: Vec3::new(0.0, 1.0, 0.0),
             defocus_angle: 0.0,
             focus_dist: 10.0,
+            shift_x: 0.0,
+            shift_y: 0.0,
+            tilt: 0.0,
+            aperture: Aperture::Circle,
+            cat_eye: 0.0,
             background: Color::new(0.0, 0.0, 0.0),
+            environment: None,
+            spectral: false,
+            model: CameraModel::Perspective,
+            stereo: None,
+            crop: None,
+            physical: None,
         }
     }
     builder_methods!(
@@ -58,34 +227,138 @@ impl CameraBuilder {
         image_width: u64,
         samples_per_pixel: u64,
         max_depth: u64,
+        filter: Filter,
         vfov: f64,
         look_from: Point,
         look_at: Point,
         vup: Vec3,
         defocus_angle: f64,
         focus_dist: f64,
+        shift_x: f64,
+        shift_y: f64,
+        tilt: f64,
+        aperture: Aperture,
+        cat_eye: f64,
         background: Color,
+        spectral: bool,
+        model: CameraModel,
     );
+    /// Lights the scene with an environment, e.g. an [`EnvironmentMap`], which rays that miss
+    /// everything see instead of `background`.
+    ///
+    /// [`EnvironmentMap`]: crate::environment::EnvironmentMap
+    pub fn environment(&mut self, environment: Arc<dyn Environment>) -> &mut Self {
+        self.environment = Some(environment);
+        self
+    }
+    /// Makes all rays parallel, for technical drawings and isometric views. `view_height` is the
+    /// height of what the camera sees in world units and replaces `vfov`.
+    pub fn orthographic(&mut self, view_height: f64) -> &mut Self {
+        self.model(CameraModel::Orthographic { view_height })
+    }
+    /// Renders a left and right eye `ipd` apart into one image, each of them `image_width` wide,
+    /// for viewing in VR. Things `convergence` away from the camera end up at the same place in
+    /// both eyes, and it can be infinite for parallel eyes.
+    ///
+    /// Perspective and orthographic cameras shift the view of each eye sideways instead of
+    /// turning it, so that vertical lines stay vertical. Equirectangular and fisheye cameras
+    /// move each ray to the side of the center that its eye would be on when looking in that
+    /// direction, which is known as omni-directional stereo.
+    pub fn stereo(&mut self, ipd: f64, convergence: f64, layout: StereoLayout) -> &mut Self {
+        self.stereo = Some(Stereo { ipd, convergence, layout });
+        self
+    }
+    /// Only renders the pixels in columns `x` and rows `y` of the image, framed the same as the
+    /// whole image, to quickly look at part of it. With a stereo camera, this is the same part of
+    /// the image of each eye.
+    ///
+    /// Panics if either range is empty, and [`build`](Self::build) panics if they don't fit in the
+    /// image.
+    pub fn crop(&mut self, x: Range<u64>, y: Range<u64>, output: CropOutput) -> &mut Self {
+        assert!(!x.is_empty() && !y.is_empty(), "crop region {x:?} by {y:?} is empty");
+        self.crop = Some(Crop {
+            x: (x.start, x.end),
+            y: (y.start, y.end),
+            output,
+        });
+        self
+    }
+    /// Sets up the camera like a real one, see [`PhysicalCamera`]. `focus_dist` is still used to
+    /// focus the lens.
+    pub fn physical(&mut self, camera: PhysicalCamera) -> &mut Self {
+        self.physical = Some(camera);
+        self
+    }
+    /// Moves the camera to where it is at a keyframe, e.g. one from [`CameraPath::at`].
+    pub fn keyframe(&mut self, keyframe: Keyframe) -> &mut Self {
+        let Keyframe { look_from, look_at, vfov, focus_dist, .. } = keyframe;
+        self.look_from(look_from).look_at(look_at).vfov(vfov).focus_dist(focus_dist)
+    }
+    /// Focuses on whatever is in the center of the image, see [`Self::autofocus_on`].
+    pub fn autofocus(&mut self, world: &ObjectList) -> &mut Self {
+        let camera = self.build();
+        self.autofocus_on(world, camera.image_width / 2, camera.image_height / 2)
+    }
+    /// Sets `focus_dist` so that the lens focuses on what the pixel at i, j sees in `world`, and
+    /// leaves it as is if there is nothing there. This has to come after the settings that frame
+    /// the image.
+    pub fn autofocus_on(&mut self, world: &ObjectList, i: u64, j: u64) -> &mut Self {
+        if let Some(focus_dist) = self.build().focus_distance(world, i, j) {
+            self.focus_dist = focus_dist;
+        }
+        self
+    }
     pub fn build(&self) -> Camera {
         let Self {
             image_width,
             aspect_ratio,
             samples_per_pixel,
             max_depth,
-            vfov,
+            filter,
+            mut vfov,
             look_from,
             look_at,
             vup,
-            defocus_angle,
+            mut defocus_angle,
             focus_dist,
+            shift_x,
+            shift_y,
+            tilt,
+            ref aperture,
+            cat_eye,
             background,
+            ref environment,
+            spectral,
+            model,
+            stereo,
+            crop,
+            physical,
         } = *self;
+        let mut exposure = 1.0;
+        let mut shutter = Interval::new(0.0, 0.0);
+        if let Some(physical) = physical {
+            vfov = physical.vfov(aspect_ratio);
+            defocus_angle = 2.0 * (physical.aperture_radius() / focus_dist).atan().to_degrees();
+            exposure = physical.exposure();
+            shutter = Interval::new(0.0, physical.shutter);
+        }
         let image_height = (image_width as f64 / aspect_ratio) as u64;
         let image_height = image_height.max(1);
+        if let Some(Crop { x, y, .. }) = crop {
+            assert!(
+                x.1 <= image_width && y.1 <= image_height,
+                "crop region {x:?} by {y:?} doesn't fit in a {image_width}x{image_height} image",
+            );
+        }
 
-        let theta = vfov.to_radians();
-        let h = (theta / 2.0).tan();
-        let viewport_height = 2.0 * h * focus_dist;
+        let viewport_height = match model {
+            CameraModel::Orthographic { view_height } => view_height,
+            _ => {
+                let theta = vfov.to_radians();
+                let h = (theta / 2.0).tan();
+                2.0 * h * focus_dist
+            }
+        };
         let viewport_width = viewport_height * (image_width as f64 / image_height as f64);
         let center = look_from;
 
@@ -103,86 +376,160 @@ impl CameraBuilder {
         let pixel_delta_v = viewport_v / image_height as f64;
 
         // location of the upper left pixel
-        let viewport_upper_left = center - (focus_dist * w) - viewport_u / 2.0 - viewport_v / 2.0;
+        let viewport_upper_left = center - (focus_dist * w) - viewport_u / 2.0 - viewport_v / 2.0
+            + shift_x * viewport_u
+            - shift_y * viewport_v;
         let pixel00_loc = viewport_upper_left + (pixel_delta_u + pixel_delta_v) * 0.5;
 
         let defocus_radius = focus_dist * (defocus_angle / 2.0).to_radians().tan();
         let sqrt_spp = (samples_per_pixel as f64).sqrt() as u64;
-        let pixel_samples_scale = 1.0 / (sqrt_spp as f64 * sqrt_spp as f64);
         let recip_sqrt_spp = (sqrt_spp as f64).recip();
         Camera {
             image_width,
             image_height,
             background,
+            environment: environment.clone(),
             pixel00_loc,
             pixel_delta_u,
             pixel_delta_v,
             sqrt_spp,
             recip_sqrt_spp,
-            pixel_samples_scale,
+            filter,
             max_depth,
             center,
             defocus_angle,
             defocus_disk_u: defocus_radius * u,
             defocus_disk_v: defocus_radius * v,
+            aperture: aperture.clone(),
+            cat_eye,
+            spectral,
+            model,
+            stereo,
+            crop,
+            exposure,
+            shutter,
+            focus_dist,
+            focus_normal: (tilt.to_radians().cos() * w) + (tilt.to_radians().sin() * v),
+            u,
+            v,
+            w,
         }
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Camera {
     image_width: u64,
     image_height: u64,
     background: Color,
+    environment: Option<Arc<dyn Environment>>,
     pixel00_loc: Point,
     pixel_delta_u: Vec3,
     pixel_delta_v: Vec3,
     defocus_angle: f64,
     defocus_disk_u: Vec3,
     defocus_disk_v: Vec3,
-    pixel_samples_scale: f64,
+    aperture: Aperture,
+    cat_eye: f64,
+    filter: Filter,
     /// Square root of # of samples per pixel
     sqrt_spp: u64,
     /// 1 / sqrt_spp
     recip_sqrt_spp: f64,
     max_depth: u64,
     center: Point,
+    spectral: bool,
+    model: CameraModel,
+    stereo: Option<Stereo>,
+    /// Within the image.
+    crop: Option<Crop>,
+    /// Scales the radiance arriving at the camera.
+    exposure: f64,
+    shutter: Interval,
+    focus_dist: f64,
+    /// Normal of the focus plane, which goes through the point `focus_dist` in front of the
+    /// camera.
+    focus_normal: Vec3,
+    /// Right, up and back from the camera.
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
 }
 
 impl Camera {
+    /// How many pixels are rendered.
     pub fn num_pixels(&self) -> u64 {
-        self.image_height * self.image_width
+        let (x, y) = self.region();
+        (x.end - x.start) * (y.end - y.start) * self.eyes().len() as u64
     }
-    pub fn render(self, world: ObjectList, lights: &dyn Object) -> color_eyre::Result<()> {
-        let Camera {
-            image_width,
-            image_height,
-            pixel_samples_scale,
-            ..
-        } = self;
-
+    /// Distance from the lens to what the center of the pixel at i, j sees, along the view
+    /// direction.
+    fn focus_distance(&self, world: &ObjectList, i: u64, j: u64) -> Option<f64> {
+        let pinhole = Camera { defocus_angle: 0.0, ..self.clone() };
+        let ray = pinhole.lens_ray(i as f64 + 0.5, j as f64 + 0.5, Vec3::default())?;
+        let record = world.hit(ray, Interval::new(0.001, f64::INFINITY))?;
+        Some((record.point - ray.origin).dot(-self.w))
+    }
+    /// When the shutter is open, in seconds from the start of the frame, which the times of
+    /// rays for motion blur would be sampled from.
+    pub fn shutter(&self) -> Interval {
+        self.shutter
+    }
+    /// The columns and rows of the image that are rendered.
+    fn region(&self) -> (Range<u64>, Range<u64>) {
+        match self.crop {
+            Some(Crop { x, y, .. }) => (x.0..x.1, y.0..y.1),
+            None => (0..self.image_width, 0..self.image_height),
+        }
+    }
+    /// Size of the saved image of each eye, and where it starts in the image.
+    fn eye_output(&self) -> ((u64, u64), (u64, u64)) {
+        match self.crop {
+            Some(Crop { x, y, output: CropOutput::Cropped }) => {
+                ((x.1 - x.0, y.1 - y.0), (x.0, y.0))
+            }
+            _ => ((self.image_width, self.image_height), (0, 0)),
+        }
+    }
+    /// Size of the saved image, which holds both eyes of a stereo camera.
+    fn output_size(&self) -> (u64, u64) {
+        let ((width, height), _) = self.eye_output();
+        match self.stereo.map(|stereo| stereo.layout) {
+            None => (width, height),
+            Some(StereoLayout::SideBySide) => (2 * width, height),
+            Some(StereoLayout::TopBottom) => (width, 2 * height),
+        }
+    }
+    /// Renders `world` and saves the image to `path`.
+    pub fn render(
+        self,
+        world: &ObjectList,
+        lights: &Lights,
+        path: impl AsRef<Path>,
+    ) -> color_eyre::Result<()> {
+        let (image_width, image_height) = self.output_size();
         let mut buffer = vec![0u8; (image_height * image_width * 3) as usize];
 
-        buffer
-            .par_chunks_exact_mut(3 * image_width as usize)
-            .enumerate()
-            .for_each(|(j, buf)| {
-                buf.par_chunks_exact_mut(3)
-                    .enumerate()
-                    .for_each(|(i, buf)| {
-                        let mut pixel_color = Color::new(0.0, 0.0, 0.0);
-                        for s_i in 0..self.sqrt_spp {
-                            for s_j in 0..self.sqrt_spp {
-                                let ray = self.get_ray(i as u64, j as u64, s_i, s_j);
-                                pixel_color += self.ray_color(ray, self.max_depth, &world, &lights);
-                                pixel_color.assert_finite();
-                            }
-                        }
-                        (pixel_samples_scale * pixel_color).write_to_buf(buf);
-                    })
-            });
+        let ((eye_width, eye_height), (origin_x, origin_y)) = self.eye_output();
+        let (region_x, region_y) = self.region();
+        for (k, eye) in self.eyes().into_iter().enumerate() {
+            let film = self.render_eye(world, lights, eye);
+            // where the image of this eye goes
+            let (left, top) = match self.stereo.map(|stereo| stereo.layout) {
+                None => (0, 0),
+                Some(StereoLayout::SideBySide) => (k as u64 * eye_width, 0),
+                Some(StereoLayout::TopBottom) => (0, k as u64 * eye_height),
+            };
+            for j in region_y.clone() {
+                for i in region_x.clone() {
+                    let (x, y) = (left + i - origin_x, top + j - origin_y);
+                    let start = ((y * image_width + x) * 3) as usize;
+                    (self.exposure * film.pixel(i, j)).write_to_buf(&mut buffer[start..start + 3]);
+                }
+            }
+        }
         image::save_buffer(
-            "image.png",
+            path,
             &buffer,
             image_width as u32,
             image_height as u32,
@@ -190,21 +537,134 @@ impl Camera {
         )?;
         Ok(())
     }
-    /// A ray originating from the defocus disk and directed at a random point around
-    /// the pixel located at i, j for stratified sample square s_i, s_j.
-    pub fn get_ray(&self, i: u64, j: u64, s_i: u64, s_j: u64) -> Ray {
-        let (offset_x, offset_y) = self.sample_square_stratified(s_i, s_j);
-
-        let pixel_sample = self.pixel00_loc
-            + ((i as f64 + offset_x) * self.pixel_delta_u)
-            + ((j as f64 + offset_y) * self.pixel_delta_v);
-        let origin = if self.defocus_angle <= 0.0 {
+    /// Renders the image of an eye that is `eye` to the right of the center. Each row is rendered
+    /// on a strip of film with the rows around it that its samples reach, which is then added to
+    /// the film of the whole image.
+    fn render_eye(&self, world: &ObjectList, lights: &Lights, eye: f64) -> Film {
+        let film = Mutex::new(Film::new(self.image_width, self.image_height, self.filter));
+        // pixels just outside of the region have samples that the filter spreads into it
+        let margin = (self.filter.radius() - 0.5).ceil().max(0.0) as u64;
+        // rows away from its own that a sample can count for
+        let reach = self.filter.radius().ceil() as u64;
+        let (x, y) = self.region();
+        let columns = x.start.saturating_sub(margin)..(x.end + margin).min(self.image_width);
+        let rows = y.start.saturating_sub(margin)..(y.end + margin).min(self.image_height);
+        rows.into_par_iter().for_each(|j| {
+            let strip_rows = j.saturating_sub(reach)..(j + reach + 1).min(self.image_height);
+            let mut strip = Film::rows(self.image_width, strip_rows, self.filter);
+            for i in columns.clone() {
+                for s_i in 0..self.sqrt_spp {
+                    for s_j in 0..self.sqrt_spp {
+                        let (offset_x, offset_y) = self.sample_square_stratified(s_i, s_j);
+                        let (x, y) = (i as f64 + 0.5 + offset_x, j as f64 + 0.5 + offset_y);
+                        let color = self.sample(x, y, eye, world, lights);
+                        strip.add_sample(x, y, color);
+                    }
+                }
+            }
+            film.lock().unwrap().add(&strip);
+        });
+        film.into_inner().unwrap()
+    }
+    /// The light arriving through the point `x`, `y` of the image of an eye, which is black if
+    /// the camera doesn't see anything there.
+    fn sample(&self, x: f64, y: f64, eye: f64, world: &ObjectList, lights: &Lights) -> Color {
+        let Some(ray) = self.get_ray(x, y, eye) else {
+            return Color::default();
+        };
+        let color = if self.spectral {
+            let mut lambda = SampledWavelengths::sample();
+            self.ray_color(ray, self.max_depth, world, lights, &mut lambda).to_rgb(&lambda)
+        } else {
+            self.ray_color(ray, self.max_depth, world, lights, &mut Rgb)
+        };
+        color.assert_finite();
+        color
+    }
+    /// A ray through the point `x`, `y` pixels from the top left corner of the image, for an
+    /// eye that is `eye` to the right of the center, or `None` if the camera doesn't see
+    /// anything there.
+    pub fn get_ray(&self, x: f64, y: f64, eye: f64) -> Option<Ray> {
+        let direction: Vec3 = match self.model {
+            CameraModel::Perspective | CameraModel::Orthographic { .. } => {
+                return self.lens_ray(x, y, eye * self.u);
+            }
+            CameraModel::Equirectangular => {
+                let phi = (x / self.image_width as f64 - 0.5) * TAU;
+                let theta = y / self.image_height as f64 * PI;
+                Vec3(theta.sin() * phi.sin(), theta.cos(), -theta.sin() * phi.cos())
+            }
+            CameraModel::Fisheye { fov, mapping } => {
+                // the image circle touches the shorter sides of the image
+                let radius = self.image_width.min(self.image_height) as f64 / 2.0;
+                let dx = (x - self.image_width as f64 / 2.0) / radius;
+                let dy = (self.image_height as f64 / 2.0 - y) / radius;
+                let r = dx.hypot(dy);
+                if r > 1.0 {
+                    return None;
+                }
+                let theta_max = (fov / 2.0).to_radians();
+                let theta = match mapping {
+                    FisheyeMapping::Equidistant => r * theta_max,
+                    FisheyeMapping::Equisolid => 2.0 * (r * (theta_max / 2.0).sin()).asin(),
+                };
+                let phi = dy.atan2(dx);
+                let sin_theta = theta.sin();
+                Vec3(sin_theta * phi.cos(), sin_theta * phi.sin(), -theta.cos())
+            }
+        };
+        // the eye is to the right of the direction, on the horizontal plane
+        let (sin_phi, cos_phi) = direction.0.atan2(-direction.2).sin_cos();
+        let eye = Vec3(eye * cos_phi, 0.0, eye * sin_phi);
+        let Vec3(x, y, z) = direction - eye / self.convergence();
+        let direction = x * self.u + y * self.v + z * self.w;
+        let Vec3(x, y, z) = eye;
+        let origin = self.center + x * self.u + y * self.v + z * self.w;
+        Some(Ray { origin, direction })
+    }
+    /// How far to the right of the center each eye is.
+    fn eyes(&self) -> Vec<f64> {
+        match self.stereo {
+            None => vec![0.0],
+            Some(stereo) => vec![-0.5 * stereo.ipd, 0.5 * stereo.ipd],
+        }
+    }
+    fn convergence(&self) -> f64 {
+        self.stereo.map_or(f64::INFINITY, |stereo| stereo.convergence)
+    }
+    /// A ray from the defocus disk through the point `x`, `y` pixels from the top left corner of
+    /// the viewport, for an eye that is `eye` away from the center, or `None` if the lens barrel
+    /// blocks it.
+    fn lens_ray(&self, x: f64, y: f64, eye: Vec3) -> Option<Ray> {
+        let pixel_sample = self.pixel00_loc + (x - 0.5) * self.pixel_delta_u
+            + (y - 0.5) * self.pixel_delta_v;
+        let pinhole = if let CameraModel::Orthographic { .. } = self.model {
+            // the lens moves along with the pixel, so that rays are parallel when in focus
+            pixel_sample + self.focus_dist * self.w
+        } else {
             self.center
+        };
+        let lens_offset = if self.defocus_angle <= 0.0 {
+            Vec3::default()
         } else {
-            self.defocus_disk_sample()
+            self.defocus_disk_sample(x, y)? - self.center
         };
-        let direction = pixel_sample - origin;
-        Ray { origin, direction }
+        let origin = pinhole + lens_offset + eye;
+
+        // the ray through the middle of the lens crosses the focus plane at the pixel, unless
+        // the plane is tilted
+        let to_pixel = pixel_sample - pinhole;
+        let to_plane = self.center - self.focus_dist * self.w - pinhole;
+        let along = to_plane.dot(self.focus_normal) / to_pixel.dot(self.focus_normal);
+        if !(along > 0.0 && along.is_finite()) {
+            // the plane is in focus infinitely far away in this direction
+            return Some(Ray { origin, direction: to_pixel });
+        }
+        // the view of the eye is shifted to meet the other one at the convergence distance
+        let focus_point = pinhole + along * to_pixel
+            + eye * (1.0 - self.focus_dist / self.convergence());
+        let direction = focus_point - origin;
+        Some(Ray { origin, direction })
     }
     /// vector to a random point in the square from (-0.5, -0.5) to (0.5, 0.5)
     pub fn sample_square() -> (f64, f64) {
@@ -217,49 +677,109 @@ impl Camera {
         let py = ((s_j as f64 + random_double()) * self.recip_sqrt_spp) - 0.5;
         (px, py)
     }
-    pub fn defocus_disk_sample(&self) -> Point {
-        let p = Point::random_in_unit_disk();
-        self.center + (p.0 * self.defocus_disk_u) + (p.1 * self.defocus_disk_v)
+    /// A random point on the aperture as seen from `x`, `y` pixels from the top left corner of
+    /// the image, or `None` if the lens barrel hides it from there.
+    pub fn defocus_disk_sample(&self, x: f64, y: f64) -> Option<Point> {
+        let (lens_x, lens_y) = self.aperture.sample();
+        if self.cat_eye > 0.0 {
+            // the barrel is a circle just around the aperture, off center towards the middle of
+            // the image by up to `cat_eye` times its radius in the corners
+            let radius = self.aperture.radius();
+            let (half_width, half_height) =
+                (self.image_width as f64 / 2.0, self.image_height as f64 / 2.0);
+            let offset = self.cat_eye * radius / half_width.hypot(half_height);
+            let barrel_x = -offset * (x - half_width);
+            let barrel_y = -offset * (half_height - y);
+            if (lens_x - barrel_x).hypot(lens_y - barrel_y) > radius {
+                return None;
+            }
+        }
+        Some(self.center + (lens_x * self.defocus_disk_u) + (lens_y * self.defocus_disk_v))
     }
     // todo condense params
-    pub fn ray_color(&self, r: Ray, depth: u64, world: &ObjectList, lights: &dyn Object) -> Color {
+    /// The light arriving along `r`, in RGB or at the wavelengths of `lambda`. Sampled
+    /// wavelengths may have their secondary wavelengths terminated along the way, which
+    /// `SampledSpectrum::to_rgb` has to know about.
+    pub fn ray_color<W: Wavelengths>(
+        &self,
+        r: Ray,
+        depth: u64,
+        world: &ObjectList,
+        lights: &Lights,
+        lambda: &mut W,
+    ) -> W::Radiance {
         if depth == 0 {
-            return Color::new(0.0, 0.0, 0.0);
+            return W::Radiance::default();
         }
         if let Some(record) = world.hit(r, Interval::new(0.001, f64::INFINITY)) {
-            let color_from_emission = record.material.emitted(&r, &record, record.point);
-            color_from_emission.assert_finite();
-            let Some(srec) = record.material.scatter(&r, &record) else {
+            let emitted = record.material.emitted(&r, &record, record.point);
+            emitted.assert_finite();
+            let color_from_emission = lambda.upsample(emitted);
+            let Some(srec) = lambda.scatter(record.material, &r, &record) else {
                 return color_from_emission;
             };
+            let attenuation = lambda.upsample(srec.attenuation);
 
             if let Some(ray) = srec.skip_pdf {
-                return srec.attenuation * self.ray_color(ray, depth-1, world, lights)
+                return attenuation * self.ray_color(ray, depth-1, world, lights, lambda)
             }
 
-            let light_pdf = ObjectPdf::new(lights, record.point);
-            let mixed = MixturePdf::new(light_pdf, srec.pdf);
+            let color_from_lights =
+                attenuation * lambda.upsample(self.sample_delta_lights(&r, &record, world, lights));
+
+            let mixed = lights.mixed_pdf(record.point, srec.pdf, self.environment.as_deref());
         
             let scattered = Ray { origin: record.point, direction: mixed.generate() };
             let pdf_value = mixed.value(scattered.direction);
 
             if pdf_value == 0. {
-                return color_from_emission;
+                return color_from_emission + color_from_lights;
             }
 
             let scattering_pdf = record.material.scattering_pdf(&r, &record, &scattered);
             // let pdf_value = scattering_pdf;
 
-            let sample_color = self.ray_color(scattered, depth-1, world, lights);
+            let sample_color = self.ray_color(scattered, depth-1, world, lights, lambda);
 
             let color_from_scatter =
-                srec.attenuation * scattering_pdf * sample_color
-                    / pdf_value;
-            color_from_scatter.assert_finite();
+                attenuation * sample_color * (scattering_pdf / pdf_value);
+            assert!(color_from_scatter.is_finite());
 
-            color_from_emission + color_from_scatter
+            color_from_emission + color_from_lights + color_from_scatter
         } else {
-            self.background
+            lambda.upsample(self.background(r.direction))
         }
     }
+    /// What a ray going in `direction` sees if it misses everything.
+    fn background(&self, direction: Vec3) -> Color {
+        match &self.environment {
+            Some(environment) => environment.radiance(direction.unit_vector()),
+            None => self.background,
+        }
+    }
+    /// Light arriving straight from the delta lights, which scattered rays can never hit. This is
+    /// weighted by the material's scattering pdf but not its attenuation.
+    fn sample_delta_lights(
+        &self,
+        r: &Ray,
+        record: &HitRecord,
+        world: &ObjectList,
+        lights: &Lights,
+    ) -> Color {
+        lights
+            .delta()
+            .iter()
+            .map(|light| {
+                let sample = light.sample(record.point);
+                let shadow_ray = Ray { origin: record.point, direction: sample.direction };
+                let scattering_pdf = record.material.scattering_pdf(r, record, &shadow_ray);
+                if scattering_pdf <= 0.0
+                    || world.hit(shadow_ray, Interval::new(0.001, sample.distance)).is_some()
+                {
+                    return Color::default();
+                }
+                scattering_pdf * sample.radiance
+            })
+            .sum()
+    }
 }
@@ -0,0 +1,124 @@
+use std::ops::{Add, Mul, Sub};
+
+use crate::vec3::Point;
+
+/// Where the camera is and what it looks at `time` seconds into an animation.
+#[derive(Clone, Copy)]
+pub struct Keyframe {
+    pub time: f64,
+    pub look_from: Point,
+    pub look_at: Point,
+    /// Vertical view angle
+    pub vfov: f64,
+    pub focus_dist: f64,
+}
+
+/// Keyframes that the camera moves through smoothly, along a Catmull-Rom spline that also
+/// evens out changes of speed between keyframes that are unevenly spaced in time.
+#[derive(Default)]
+pub struct CameraPath {
+    /// Sorted by time.
+    keyframes: Vec<Keyframe>,
+}
+
+impl CameraPath {
+    /// Adds a keyframe, replacing the one at the same time if there is one.
+    pub fn add(&mut self, keyframe: Keyframe) {
+        let index = self.keyframes.partition_point(|k| k.time < keyframe.time);
+        match self.keyframes.get_mut(index) {
+            Some(k) if k.time == keyframe.time => *k = keyframe,
+            _ => self.keyframes.insert(index, keyframe),
+        }
+    }
+
+    /// Time of the first keyframe.
+    pub fn start(&self) -> f64 {
+        self.keyframes.first().map_or(0.0, |k| k.time)
+    }
+
+    /// Time from the first keyframe to the last.
+    pub fn duration(&self) -> f64 {
+        self.keyframes.last().map_or(0.0, |k| k.time) - self.start()
+    }
+
+    /// The camera at `time`, which stays at the first or last keyframe outside of them.
+    pub fn at(&self, time: f64) -> Keyframe {
+        let keyframes = &self.keyframes;
+        assert!(!keyframes.is_empty(), "camera path must have at least one keyframe");
+        let i = keyframes.partition_point(|k| k.time <= time).saturating_sub(1);
+        if i == keyframes.len() - 1 || time <= keyframes[0].time {
+            return Keyframe { time, ..keyframes[i] };
+        }
+        Keyframe {
+            time,
+            look_from: self.interpolate(i, time, |k| k.look_from),
+            look_at: self.interpolate(i, time, |k| k.look_at),
+            vfov: self.interpolate(i, time, |k| k.vfov),
+            focus_dist: self.interpolate(i, time, |k| k.focus_dist),
+        }
+    }
+
+    /// One value of the keyframes at `time`, between keyframe `i` and the next.
+    fn interpolate<T>(&self, i: usize, time: f64, value: impl Fn(&Keyframe) -> T) -> T
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f64, Output = T>,
+    {
+        let keyframes = &self.keyframes;
+        // change per second, from the keyframes on either side
+        let tangent = |j: usize| {
+            let a = &keyframes[j.saturating_sub(1)];
+            let b = &keyframes[(j + 1).min(keyframes.len() - 1)];
+            (value(b) - value(a)) * (b.time - a.time).recip()
+        };
+        let (k0, k1) = (&keyframes[i], &keyframes[i + 1]);
+        let span = k1.time - k0.time;
+        let t = (time - k0.time) / span;
+
+        let (t2, t3) = (t * t, t * t * t);
+        value(k0) * (2.0 * t3 - 3.0 * t2 + 1.0)
+            + tangent(i) * (span * (t3 - 2.0 * t2 + t))
+            + value(k1) * (-2.0 * t3 + 3.0 * t2)
+            + tangent(i + 1) * (span * (t3 - t2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyframe(time: f64, x: f64) -> Keyframe {
+        Keyframe {
+            time,
+            look_from: Point::new(x, 0.0, 0.0),
+            look_at: Point::new(0.0, 0.0, -1.0),
+            vfov: 40.0,
+            focus_dist: 1.0,
+        }
+    }
+
+    #[test]
+    fn same_time_replaces() {
+        let mut path = CameraPath::default();
+        path.add(keyframe(0.0, 0.0));
+        path.add(keyframe(1.0, 1.0));
+        path.add(keyframe(1.0, 2.0));
+        path.add(keyframe(2.0, 3.0));
+        assert_eq!(path.keyframes.len(), 3);
+
+        for i in 0..=20 {
+            let time = i as f64 * 0.1;
+            let x = path.at(time).look_from.0;
+            assert!(x.is_finite(), "{x} at {time}");
+        }
+        assert_eq!(path.at(1.0).look_from.0, 2.0);
+    }
+
+    #[test]
+    fn duration_from_first_keyframe() {
+        let mut path = CameraPath::default();
+        path.add(keyframe(2.0, 0.0));
+        path.add(keyframe(5.0, 1.0));
+        assert_eq!(path.start(), 2.0);
+        assert_eq!(path.duration(), 3.0);
+    }
+}
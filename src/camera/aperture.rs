@@ -0,0 +1,83 @@
+use std::f64::consts::{SQRT_2, TAU};
+use std::path::Path;
+use std::sync::Arc;
+
+use image::{ImageBuffer, Luma};
+
+use crate::distribution::Distribution2D;
+use crate::utils::random_double;
+use crate::vec3::Point;
+
+/// The shape of the opening of the lens, which out of focus highlights take on.
+#[derive(Clone)]
+pub enum Aperture {
+    Circle,
+    /// A regular polygon formed by `blades` straight aperture blades, with a corner at `rotation`
+    /// degrees counterclockwise from the right.
+    Polygon { blades: u32, rotation: f64 },
+    Mask(Arc<ApertureMask>),
+}
+
+impl Aperture {
+    /// Radius of the smallest circle around the center that contains the whole aperture.
+    pub(super) fn radius(&self) -> f64 {
+        match self {
+            Aperture::Circle | Aperture::Polygon { .. } => 1.0,
+            // the corners of the square
+            Aperture::Mask(_) => SQRT_2,
+        }
+    }
+
+    /// A random point on the aperture, in the square from (-1, -1) to (1, 1) with y up.
+    pub(super) fn sample(&self) -> (f64, f64) {
+        match self {
+            Aperture::Circle => {
+                let p = Point::random_in_unit_disk();
+                (p.0, p.1)
+            }
+            &Aperture::Polygon { blades, rotation } => {
+                // a random one of the triangles between the center and each side, all of which
+                // have the same area
+                let blades = blades.max(3) as f64;
+                let side = (random_double() * blades).floor();
+                let corner = |k: f64| {
+                    let (sin, cos) = (rotation.to_radians() + k * TAU / blades).sin_cos();
+                    (cos, sin)
+                };
+                let (a, b) = (corner(side), corner(side + 1.0));
+                let (mut s, mut t) = (random_double(), random_double());
+                if s + t > 1.0 {
+                    (s, t) = (1.0 - s, 1.0 - t);
+                }
+                (s * a.0 + t * b.0, s * a.1 + t * b.1)
+            }
+            Aperture::Mask(mask) => {
+                let (x, y) = mask.distribution.sample(random_double(), random_double());
+                (2.0 * x - 1.0, 1.0 - 2.0 * y)
+            }
+        }
+    }
+}
+
+/// An aperture given by an image, where white lets light through and black blocks it. The image
+/// is stretched over the square around the lens.
+pub struct ApertureMask {
+    distribution: Distribution2D,
+}
+
+impl ApertureMask {
+    /// Loads a grayscale image, whose values are used as is.
+    pub fn load(path: impl AsRef<Path>) -> color_eyre::Result<Self> {
+        Ok(Self::new(image::open(path)?.to_luma32f()))
+    }
+
+    pub fn new(image: ImageBuffer<Luma<f32>, Vec<f32>>) -> Self {
+        let func = image
+            .rows()
+            .map(|row| row.map(|pixel| pixel.0[0] as f64).collect())
+            .collect();
+        Self {
+            distribution: Distribution2D::new(func),
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use std::f64::consts::PI;
+use std::ops::Range;
+
+use crate::color::Color;
+
+/// How much a sample counts towards each pixel around it, by its distance from the pixel's
+/// center. Every filter is the product of a horizontal and a vertical one, and `radius` is in
+/// pixels.
+#[derive(Clone, Copy)]
+pub enum Filter {
+    /// Each sample counts fully for every pixel within `radius`, which averages the samples in
+    /// each pixel for 0.5.
+    Box { radius: f64 },
+    /// Falls off linearly, which is a little blurry.
+    Tent { radius: f64 },
+    /// Falls off like a normal distribution with standard deviation `sigma`, shifted down to
+    /// reach 0 at `radius`.
+    Gaussian { radius: f64, sigma: f64 },
+    /// The cubic of Mitchell and Netravali, a tradeoff between blurring and ringing set by `b`
+    /// and `c`. They recommend b = c = 1/3 with a radius of 2.
+    Mitchell { radius: f64, b: f64, c: f64 },
+    /// A sinc windowed by a wider sinc with `tau` lobes, which keeps the most detail but rings
+    /// around sharp edges.
+    Lanczos { radius: f64, tau: f64 },
+}
+
+impl Filter {
+    pub fn radius(&self) -> f64 {
+        match *self {
+            Filter::Box { radius }
+            | Filter::Tent { radius }
+            | Filter::Gaussian { radius, .. }
+            | Filter::Mitchell { radius, .. }
+            | Filter::Lanczos { radius, .. } => radius,
+        }
+    }
+
+    /// The weight of a sample `dx`, `dy` pixels from the center of a pixel.
+    fn evaluate(&self, dx: f64, dy: f64) -> f64 {
+        self.evaluate_1d(dx) * self.evaluate_1d(dy)
+    }
+
+    fn evaluate_1d(&self, x: f64) -> f64 {
+        let x = x.abs();
+        match *self {
+            Filter::Box { radius } => f64::from(x <= radius),
+            Filter::Tent { radius } => (radius - x).max(0.0),
+            Filter::Gaussian { radius, sigma } => {
+                let gaussian = |x: f64| (-x * x / (2.0 * sigma * sigma)).exp();
+                (gaussian(x) - gaussian(radius)).max(0.0)
+            }
+            Filter::Mitchell { radius, b, c } => {
+                // the cubic is defined over [-2, 2]
+                let x = 2.0 * x / radius;
+                let polynomial = if x > 2.0 {
+                    0.0
+                } else if x > 1.0 {
+                    (-b - 6.0 * c) * x.powi(3)
+                        + (6.0 * b + 30.0 * c) * x.powi(2)
+                        + (-12.0 * b - 48.0 * c) * x
+                        + (8.0 * b + 24.0 * c)
+                } else {
+                    (12.0 - 9.0 * b - 6.0 * c) * x.powi(3)
+                        + (-18.0 + 12.0 * b + 6.0 * c) * x.powi(2)
+                        + (6.0 - 2.0 * b)
+                };
+                polynomial / 6.0
+            }
+            Filter::Lanczos { radius, tau } => {
+                if x > radius {
+                    0.0
+                } else {
+                    sinc(x) * sinc(x / tau)
+                }
+            }
+        }
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x < 1e-5 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// The image that samples are accumulated on. Each sample is added to every pixel in reach of
+/// the filter, weighted by it, and each pixel is the weighted average of its samples.
+///
+/// A film can also hold just some of the rows of the image, so that threads can each fill a
+/// small one that is then added to the whole image.
+pub struct Film {
+    width: u64,
+    /// The rows of the image that this holds.
+    rows: Range<u64>,
+    filter: Filter,
+    /// Weighted sum of the samples and the sum of their weights for each pixel, row by row.
+    pixels: Vec<(Color, f64)>,
+}
+
+impl Film {
+    pub fn new(width: u64, height: u64, filter: Filter) -> Self {
+        Self::rows(width, 0..height, filter)
+    }
+
+    /// A film of the rows `rows` of an image that is `width` pixels wide. Samples only count for
+    /// pixels in these rows.
+    pub fn rows(width: u64, rows: Range<u64>, filter: Filter) -> Self {
+        Self {
+            width,
+            pixels: vec![(Color::default(), 0.0); (width * (rows.end - rows.start)) as usize],
+            rows,
+            filter,
+        }
+    }
+
+    /// Adds a sample at `x`, `y` pixels from the top left corner of the image.
+    pub fn add_sample(&mut self, x: f64, y: f64, color: Color) {
+        let radius = self.filter.radius();
+        // pixels whose centers are within the radius
+        let range = |p: f64, pixels: Range<u64>| {
+            let first = (p - 0.5 - radius).ceil().max(pixels.start as f64) as u64;
+            let last = (p - 0.5 + radius).floor().min(pixels.end as f64 - 1.0);
+            first..(last + 1.0).max(0.0) as u64
+        };
+        for j in range(y, self.rows.clone()) {
+            for i in range(x, 0..self.width) {
+                let weight = self.filter.evaluate(x - (i as f64 + 0.5), y - (j as f64 + 0.5));
+                if weight != 0.0 {
+                    let index = self.index(i, j);
+                    let (sum, total_weight) = &mut self.pixels[index];
+                    *sum += weight * color;
+                    *total_weight += weight;
+                }
+            }
+        }
+    }
+
+    /// Adds the samples of a film of the same width, whose rows must be among these.
+    pub fn add(&mut self, other: &Film) {
+        assert!(self.width == other.width, "films must be equally wide");
+        assert!(
+            self.rows.start <= other.rows.start && other.rows.end <= self.rows.end,
+            "rows {:?} aren't on a film of rows {:?}",
+            other.rows,
+            self.rows,
+        );
+        let start = self.index(0, other.rows.start);
+        let pixels = &mut self.pixels[start..start + other.pixels.len()];
+        for ((sum, weight), (other_sum, other_weight)) in pixels.iter_mut().zip(&other.pixels) {
+            *sum += *other_sum;
+            *weight += other_weight;
+        }
+    }
+
+    pub fn pixel(&self, i: u64, j: u64) -> Color {
+        let (sum, weight) = self.pixels[self.index(i, j)];
+        if weight > 0.0 {
+            sum / weight
+        } else {
+            Color::default()
+        }
+    }
+
+    fn index(&self, i: u64, j: u64) -> usize {
+        ((j - self.rows.start) * self.width + i) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3::Vec3;
+
+    const FILTERS: [Filter; 5] = [
+        Filter::Box { radius: 0.5 },
+        Filter::Tent { radius: 1.0 },
+        Filter::Gaussian { radius: 1.5, sigma: 0.5 },
+        Filter::Mitchell { radius: 2.0, b: 1.0 / 3.0, c: 1.0 / 3.0 },
+        Filter::Lanczos { radius: 3.0, tau: 3.0 },
+    ];
+
+    /// Samples on a 4×4 grid in every pixel of a `width` by `height` image, none of them on
+    /// the edge of a pixel, with colors that vary across the image.
+    fn samples(width: u64, height: u64) -> Vec<(f64, f64, Color)> {
+        let mut samples = Vec::new();
+        for j in 0..height {
+            for i in 0..width {
+                for (a, b) in (0..4).flat_map(|a| (0..4).map(move |b| (a, b))) {
+                    let x = i as f64 + (a as f64 + 0.3) / 4.0;
+                    let y = j as f64 + (b as f64 + 0.6) / 4.0;
+                    samples.push((x, y, Color::new(x, y, x * y)));
+                }
+            }
+        }
+        samples
+    }
+
+    fn assert_close(color: Color, expected: Color) {
+        let (Vec3(r, g, b), Vec3(er, eg, eb)) = (color, expected);
+        assert!((color - expected).length() < 1e-9, "({r}, {g}, {b}) != ({er}, {eg}, {eb})");
+    }
+
+    #[test]
+    fn box_averages_each_pixel() {
+        let samples = samples(4, 3);
+        let mut film = Film::new(4, 3, Filter::Box { radius: 0.5 });
+        for &(x, y, color) in &samples {
+            film.add_sample(x, y, color);
+        }
+        for j in 0..3 {
+            for i in 0..4 {
+                let inside: Vec<_> = samples
+                    .iter()
+                    .filter(|(x, y, _)| x.floor() as u64 == i && y.floor() as u64 == j)
+                    .map(|&(_, _, color)| color)
+                    .collect();
+                let average = inside.iter().fold(Color::default(), |a, &c| a + c)
+                    / inside.len() as f64;
+                assert_close(film.pixel(i, j), average);
+            }
+        }
+    }
+
+    #[test]
+    fn filters_are_normalized() {
+        let color = Color::new(0.2, 0.5, 0.9);
+        for filter in FILTERS {
+            let mut film = Film::new(6, 5, filter);
+            for (x, y, _) in samples(6, 5) {
+                film.add_sample(x, y, color);
+            }
+            for (i, j) in (0..6).flat_map(|i| (0..5).map(move |j| (i, j))) {
+                assert_close(film.pixel(i, j), color);
+            }
+        }
+    }
+
+    #[test]
+    fn strips_add_up_to_film() {
+        for filter in FILTERS {
+            let (width, height) = (5, 7);
+            let mut direct = Film::new(width, height, filter);
+            let mut from_strips = Film::new(width, height, filter);
+            let samples = samples(width, height);
+            // one strip per row, with the rows around it that its samples reach
+            let reach = filter.radius().ceil() as u64;
+            for j in 0..height {
+                let rows = j.saturating_sub(reach)..(j + reach + 1).min(height);
+                let mut strip = Film::rows(width, rows, filter);
+                for &(x, y, color) in samples.iter().filter(|(_, y, _)| y.floor() as u64 == j) {
+                    direct.add_sample(x, y, color);
+                    strip.add_sample(x, y, color);
+                }
+                from_strips.add(&strip);
+            }
+            for (i, j) in (0..width).flat_map(|i| (0..height).map(move |j| (i, j))) {
+                assert_close(from_strips.pixel(i, j), direct.pixel(i, j));
+            }
+        }
+    }
+}
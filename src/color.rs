@@ -11,6 +11,10 @@ impl Vec3Token for ColorToken {
 
 pub type Color = Vec3<ColorToken>;
 
+/// Luminance in kcd/m² that becomes a radiance of 1, chosen so that a white surface under a
+/// midday sun is just below overexposed.
+pub const UNIT_LUMINANCE: f64 = 30.0;
+
 fn linear_to_gamma(linear_component: f64) -> f64 {
     if linear_component > 0.0 {
         linear_component.sqrt()
@@ -20,6 +24,12 @@ fn linear_to_gamma(linear_component: f64) -> f64 {
 }
 
 impl Color {
+    /// Relative luminance of a linear sRGB color.
+    pub fn luminance(self) -> f64 {
+        let Vec3(r, g, b) = self;
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
     pub fn write_to(self, out: &mut impl Write) -> io::Result<()> {
         let Vec3(r, g, b) = self;
         let intensity = Interval::new(0.000, 0.999);
@@ -0,0 +1,211 @@
+/// A piecewise constant distribution over [0, 1) with one bucket per value of `func`.
+pub struct Distribution1D {
+    func: Vec<f64>,
+    cdf: Vec<f64>,
+    integral: f64,
+}
+
+impl Distribution1D {
+    pub fn new(func: Vec<f64>) -> Self {
+        assert!(!func.is_empty(), "distribution must have at least one bucket");
+        let n = func.len() as f64;
+        let mut cdf = Vec::with_capacity(func.len() + 1);
+        cdf.push(0.0);
+        for f in &func {
+            cdf.push(cdf.last().unwrap() + f.abs() / n);
+        }
+        let integral = *cdf.last().unwrap();
+        if integral == 0.0 {
+            // nothing to go by, fall back to uniform
+            for (i, c) in cdf.iter_mut().enumerate() {
+                *c = i as f64 / n;
+            }
+        } else {
+            for c in &mut cdf {
+                *c /= integral;
+            }
+        }
+        Self {
+            func,
+            cdf,
+            integral,
+        }
+    }
+
+    pub fn integral(&self) -> f64 {
+        self.integral
+    }
+
+    /// Maps a uniform `u` in [0, 1) to a sample, returning the sample and the bucket it is in.
+    pub fn sample(&self, u: f64) -> (f64, usize) {
+        let offset = (self.cdf.partition_point(|&c| c <= u) - 1).min(self.func.len() - 1);
+        let width = self.cdf[offset + 1] - self.cdf[offset];
+        let du = if width > 0.0 { (u - self.cdf[offset]) / width } else { 0.0 };
+        ((offset as f64 + du) / self.func.len() as f64, offset)
+    }
+
+    /// The density at `x` in [0, 1).
+    pub fn pdf(&self, x: f64) -> f64 {
+        if self.integral == 0.0 {
+            return 1.0;
+        }
+        self.func[self.bucket(x)].abs() / self.integral
+    }
+
+    fn bucket(&self, x: f64) -> usize {
+        ((x * self.func.len() as f64) as usize).min(self.func.len() - 1)
+    }
+}
+
+/// A piecewise constant distribution over [0, 1)², sampled by first picking a row with the
+/// marginal distribution and then a column within that row.
+pub struct Distribution2D {
+    rows: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    /// `func` is indexed by row first.
+    pub fn new(func: Vec<Vec<f64>>) -> Self {
+        let rows: Vec<_> = func.into_iter().map(Distribution1D::new).collect();
+        let marginal = Distribution1D::new(rows.iter().map(|r| r.integral()).collect());
+        Self { rows, marginal }
+    }
+
+    /// Returns the column and row coordinates of the sample.
+    pub fn sample(&self, u1: f64, u2: f64) -> (f64, f64) {
+        let (y, row) = self.marginal.sample(u2);
+        let (x, _) = self.rows[row].sample(u1);
+        (x, y)
+    }
+
+    pub fn pdf(&self, x: f64, y: f64) -> f64 {
+        let row = &self.rows[self.marginal.bucket(y)];
+        self.marginal.pdf(y) * row.pdf(x)
+    }
+}
+
+/// Picks one of a set of items with probability proportional to its weight in constant time,
+/// with Vose's alias method.
+pub struct AliasTable {
+    /// Probability of keeping the picked bucket instead of going to its alias.
+    threshold: Vec<f64>,
+    alias: Vec<usize>,
+    pmf: Vec<f64>,
+}
+
+impl AliasTable {
+    /// Weights that are all zero are treated as equal.
+    pub fn new(weights: &[f64]) -> Self {
+        assert!(!weights.is_empty(), "alias table must have at least one item");
+        let n = weights.len();
+        let total: f64 = weights.iter().map(|w| w.abs()).sum();
+        let pmf: Vec<f64> = if total > 0.0 {
+            weights.iter().map(|w| w.abs() / total).collect()
+        } else {
+            vec![1.0 / n as f64; n]
+        };
+
+        let mut threshold: Vec<f64> = pmf.iter().map(|p| p * n as f64).collect();
+        let mut alias: Vec<usize> = (0..n).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..n).partition(|&i| threshold[i] < 1.0);
+        while let (Some(s), Some(&l)) = (small.pop(), large.last()) {
+            // the small bucket is topped up by the large one
+            alias[s] = l;
+            threshold[l] -= 1.0 - threshold[s];
+            if threshold[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // whatever is left is 1 up to rounding errors
+        for i in small.into_iter().chain(large) {
+            threshold[i] = 1.0;
+        }
+
+        Self { threshold, alias, pmf }
+    }
+
+    /// Maps a uniform `u` in [0, 1) to an item.
+    pub fn sample(&self, u: f64) -> usize {
+        let scaled = u * self.threshold.len() as f64;
+        let bucket = (scaled as usize).min(self.threshold.len() - 1);
+        if scaled - (bucket as f64) < self.threshold[bucket] {
+            bucket
+        } else {
+            self.alias[bucket]
+        }
+    }
+
+    /// The probability of picking item `i`.
+    pub fn pmf(&self, i: usize) -> f64 {
+        self.pmf[i]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `n` evenly spread values in [0, 1).
+    fn stratified(n: usize) -> impl Iterator<Item = f64> {
+        (0..n).map(move |i| (i as f64 + 0.5) / n as f64)
+    }
+
+    #[test]
+    fn distribution_1d_samples_follow_pdf() {
+        let distribution = Distribution1D::new(vec![1.0, 3.0, 0.0, 2.0]);
+        let n = 10_000;
+        let mut counts = [0; 4];
+        for u in stratified(n) {
+            let (x, bucket) = distribution.sample(u);
+            assert_eq!(bucket, distribution.bucket(x));
+            counts[bucket] += 1;
+        }
+        for (i, count) in counts.into_iter().enumerate() {
+            let expected = distribution.pdf((i as f64 + 0.5) / 4.0) / 4.0;
+            let frequency = count as f64 / n as f64;
+            assert!((frequency - expected).abs() < 1e-3, "{frequency} != {expected}");
+        }
+    }
+
+    #[test]
+    fn distribution_2d_samples_follow_pdf() {
+        let func = vec![vec![1.0, 0.0, 2.0], vec![0.5, 4.0, 1.0]];
+        let distribution = Distribution2D::new(func);
+        let n = 300;
+        let mut counts = [[0; 3]; 2];
+        for u2 in stratified(n) {
+            for u1 in stratified(n) {
+                let (x, y) = distribution.sample(u1, u2);
+                counts[(y * 2.0) as usize][(x * 3.0) as usize] += 1;
+            }
+        }
+        for (j, row) in counts.into_iter().enumerate() {
+            for (i, count) in row.into_iter().enumerate() {
+                let (x, y) = ((i as f64 + 0.5) / 3.0, (j as f64 + 0.5) / 2.0);
+                let expected = distribution.pdf(x, y) / 6.0;
+                let frequency = count as f64 / (n * n) as f64;
+                assert!((frequency - expected).abs() < 1e-2, "{frequency} != {expected}");
+            }
+        }
+    }
+
+    #[test]
+    fn alias_table_picks_by_weight() {
+        let weights = [1.0, 0.0, 3.0, 6.0, 2.0];
+        let table = AliasTable::new(&weights);
+        let n = 100_000;
+        let mut counts = [0; 5];
+        for u in stratified(n) {
+            counts[table.sample(u)] += 1;
+        }
+        for (i, count) in counts.into_iter().enumerate() {
+            let expected = weights[i] / 12.0;
+            assert!((table.pmf(i) - expected).abs() < 1e-12);
+            let frequency = count as f64 / n as f64;
+            assert!((frequency - expected).abs() < 1e-3, "{frequency} != {expected}");
+        }
+    }
+}
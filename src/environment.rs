@@ -0,0 +1,222 @@
+use std::f64::consts::{PI, TAU};
+use std::fs;
+use std::path::Path;
+
+use color_eyre::eyre::{bail, ensure, OptionExt};
+use image::Rgb32FImage;
+
+use crate::color::Color;
+use crate::distribution::Distribution2D;
+use crate::utils::random_double;
+use crate::vec3::Vec3;
+
+mod sky;
+pub use sky::{Sky, Sun};
+
+/// Light arriving from infinitely far away, seen by rays that miss everything and sampled as a
+/// light at each scattering point.
+pub trait Environment: Send + Sync {
+    /// The radiance arriving from `direction`, which is a unit vector.
+    fn radiance(&self, direction: Vec3) -> Color;
+    /// A random unit vector, ideally with a density proportional to the radiance.
+    fn sample(&self) -> Vec3;
+    /// The density of [`Environment::sample`] over solid angle.
+    fn pdf(&self, direction: Vec3) -> f64;
+}
+
+/// An HDR image in equirectangular (latitude-longitude) layout, with its center in the -Z
+/// direction and the top row straight up.
+pub struct EnvironmentMap {
+    image: Rgb32FImage,
+    distribution: Distribution2D,
+    intensity: f64,
+    sin_rotation: f64,
+    cos_rotation: f64,
+}
+
+impl EnvironmentMap {
+    /// Loads a Radiance `.hdr` or a `.pfm` file.
+    pub fn load(path: impl AsRef<Path>) -> color_eyre::Result<Self> {
+        let path = path.as_ref();
+        let image = if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pfm")) {
+            read_pfm(&fs::read(path)?)?
+        } else {
+            image::open(path)?.into_rgb32f()
+        };
+        Ok(Self::new(image))
+    }
+
+    pub fn new(image: Rgb32FImage) -> Self {
+        let (width, height) = image.dimensions();
+        // Rows near the poles cover less solid angle, so they are sampled less.
+        let func = (0..height)
+            .map(|j| {
+                let sin_theta = (PI * (j as f64 + 0.5) / height as f64).sin();
+                (0..width)
+                    .map(|i| pixel_color(&image, i, j).luminance() * sin_theta)
+                    .collect()
+            })
+            .collect();
+        Self {
+            image,
+            distribution: Distribution2D::new(func),
+            intensity: 1.0,
+            sin_rotation: 0.0,
+            cos_rotation: 1.0,
+        }
+    }
+
+    /// Scales the radiance of the map.
+    pub fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Rotates the map around the Y axis by `angle` degrees.
+    pub fn with_rotation(mut self, angle: f64) -> Self {
+        (self.sin_rotation, self.cos_rotation) = angle.to_radians().sin_cos();
+        self
+    }
+
+    /// Maps a world direction to image coordinates in [0, 1)².
+    fn direction_to_uv(&self, Vec3(x, y, z): Vec3) -> (f64, f64) {
+        // undo the rotation of the map
+        let (x, z) = (
+            self.cos_rotation * x - self.sin_rotation * z,
+            self.sin_rotation * x + self.cos_rotation * z,
+        );
+        let phi = f64::atan2(x, -z);
+        let theta = y.clamp(-1.0, 1.0).acos();
+        ((0.5 + phi / TAU).rem_euclid(1.0), theta / PI)
+    }
+
+    fn uv_to_direction(&self, u: f64, v: f64) -> Vec3 {
+        let Vec3(x, y, z) = equirect_direction(u, v);
+        Vec3(
+            self.cos_rotation * x + self.sin_rotation * z,
+            y,
+            -self.sin_rotation * x + self.cos_rotation * z,
+        )
+    }
+}
+
+impl Environment for EnvironmentMap {
+    fn radiance(&self, direction: Vec3) -> Color {
+        let (u, v) = self.direction_to_uv(direction);
+        let (width, height) = self.image.dimensions();
+        let i = ((u * width as f64) as u32).min(width - 1);
+        let j = ((v * height as f64) as u32).min(height - 1);
+        self.intensity * pixel_color(&self.image, i, j)
+    }
+    fn sample(&self) -> Vec3 {
+        let (u, v) = self.distribution.sample(random_double(), random_double());
+        self.uv_to_direction(u, v)
+    }
+    fn pdf(&self, direction: Vec3) -> f64 {
+        let direction = direction.unit_vector();
+        let (u, v) = self.direction_to_uv(direction);
+        let sin_theta = (1.0 - direction.1 * direction.1).max(0.0).sqrt();
+        if sin_theta == 0.0 {
+            return 0.0;
+        }
+        // the map covers 2π by π radians of longitude and latitude
+        self.distribution.pdf(u, v) / (2.0 * PI * PI * sin_theta)
+    }
+}
+
+/// The unrotated direction at image coordinates `u` and `v` of an equirectangular map.
+fn equirect_direction(u: f64, v: f64) -> Vec3 {
+    let phi = (u - 0.5) * TAU;
+    let (sin_theta, cos_theta) = (v * PI).sin_cos();
+    Vec3(sin_theta * phi.sin(), cos_theta, -sin_theta * phi.cos())
+}
+
+fn pixel_color(image: &Rgb32FImage, i: u32, j: u32) -> Color {
+    let [r, g, b] = image.get_pixel(i, j).0;
+    Color::new(r.into(), g.into(), b.into())
+}
+
+/// Parses a portable float map, which is stored bottom row first.
+fn read_pfm(data: &[u8]) -> color_eyre::Result<Rgb32FImage> {
+    let mut header = data.splitn(4, |b| *b == b'\n');
+    let mut next = || -> color_eyre::Result<&str> {
+        let line = header.next().ok_or_eyre("truncated PFM header")?;
+        Ok(std::str::from_utf8(line)?.trim())
+    };
+    let channels = match next()? {
+        "PF" => 3,
+        "Pf" => 1,
+        magic => bail!("not a PFM file, found magic {magic:?}"),
+    };
+    let dims = next()?;
+    let (width, height) = dims
+        .split_once(char::is_whitespace)
+        .ok_or_eyre("bad PFM dimensions")?;
+    let (width, height): (u32, u32) = (width.trim().parse()?, height.trim().parse()?);
+    let scale: f32 = next()?.parse()?;
+    let pixels = header.next().ok_or_eyre("PFM has no pixel data")?;
+
+    let len = width as usize * height as usize * channels;
+    ensure!(pixels.len() >= 4 * len, "PFM pixel data is truncated");
+    let floats: Vec<f32> = pixels[..4 * len]
+        .chunks_exact(4)
+        .map(|b| {
+            let b = b.try_into().unwrap();
+            // a negative scale means little endian
+            if scale < 0.0 { f32::from_le_bytes(b) } else { f32::from_be_bytes(b) }
+        })
+        .collect();
+
+    Ok(Rgb32FImage::from_fn(width, height, |i, j| {
+        let start = ((height - 1 - j) as usize * width as usize + i as usize) * channels;
+        match channels {
+            3 => image::Rgb([floats[start], floats[start + 1], floats[start + 2]]),
+            _ => image::Rgb([floats[start]; 3]),
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uv_round_trip_with_rotation() {
+        let unrotated = EnvironmentMap::new(Rgb32FImage::new(4, 2));
+        let map = EnvironmentMap::new(Rgb32FImage::new(4, 2)).with_rotation(30.0);
+        for i in 0..8 {
+            for j in 1..8 {
+                let (u, v) = (i as f64 / 8.0, j as f64 / 8.0);
+                let direction = map.uv_to_direction(u, v);
+                let (u2, v2) = map.direction_to_uv(direction);
+                assert!((u - u2).abs() < 1e-9 && (v - v2).abs() < 1e-9, "{u2}, {v2}");
+
+                // the rotation is around the Y axis
+                let Vec3(x, y, z) = unrotated.uv_to_direction(u, v);
+                let cos = direction.dot(Vec3(x, 0.0, z)) / (x * x + z * z);
+                assert!((direction.1 - y).abs() < 1e-9);
+                assert!((cos - 30f64.to_radians().cos()).abs() < 1e-9, "{cos}");
+            }
+        }
+    }
+
+    /// A 1×2 color PFM with the given scale and float encoding.
+    fn pfm(scale: &str, to_bytes: fn(f32) -> [u8; 4]) -> Vec<u8> {
+        let mut data = format!("PF\n1 2\n{scale}\n").into_bytes();
+        // bottom row first
+        for f in [1.0, 2.0, 3.0, 4.0, 5.0, 6.0] {
+            data.extend(to_bytes(f));
+        }
+        data
+    }
+
+    #[test]
+    fn pfm_byte_orders() {
+        for data in [pfm("-1.0", f32::to_le_bytes), pfm("1.0", f32::to_be_bytes)] {
+            let image = read_pfm(&data).unwrap();
+            assert_eq!(image.dimensions(), (1, 2));
+            assert_eq!(image.get_pixel(0, 0).0, [4.0, 5.0, 6.0]);
+            assert_eq!(image.get_pixel(0, 1).0, [1.0, 2.0, 3.0]);
+        }
+    }
+}
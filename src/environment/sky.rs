@@ -0,0 +1,261 @@
+use std::array;
+use std::f64::consts::{PI, TAU};
+
+use image::Rgb32FImage;
+
+use super::{equirect_direction, Environment, EnvironmentMap};
+use crate::color::{Color, UNIT_LUMINANCE};
+use crate::onb::Onb;
+use crate::spectrum::{blackbody, spectrum_to_xyz, xyz_to_linear_srgb};
+use crate::utils::random_double;
+use crate::vec3::Vec3;
+
+/// Luminance of the sun outside the atmosphere, in kcd/m².
+const SUN_LUMINANCE: f64 = 1.6e6;
+/// Angular radius of the sun seen from the earth, in degrees.
+const SUN_ANGULAR_RADIUS: f64 = 0.265;
+const SUN_TEMPERATURE: f64 = 5778.0;
+/// Resolution of the table used to importance sample the sky.
+const TABLE_WIDTH: u32 = 64;
+const TABLE_HEIGHT: u32 = 32;
+
+/// The Perez sky luminance distribution, with coefficients A to E.
+#[derive(Clone, Copy)]
+struct Perez([f64; 5]);
+
+impl Perez {
+    /// `theta` is the angle from the zenith and `gamma` the angle from the sun.
+    fn eval(self, cos_theta: f64, gamma: f64) -> f64 {
+        let [a, b, c, d, e] = self.0;
+        let cos_gamma = gamma.cos();
+        (1.0 + a * (b / cos_theta).exp())
+            * (1.0 + c * (d * gamma).exp() + e * cos_gamma * cos_gamma)
+    }
+}
+
+/// The clear sky model from Preetham, Shirley and Smits, "A Practical Analytic Model for
+/// Daylight" (1999), with the sun in it.
+///
+/// Directions below the horizon see the sky at the horizon, so scenes should have a ground.
+pub struct Sky {
+    model: Preetham,
+    intensity: f64,
+    sun: Option<Sun>,
+    /// The sky without the sun, tabulated for sampling.
+    table: EnvironmentMap,
+    sun_probability: f64,
+}
+
+impl Sky {
+    /// A sky with the sun in `sun_direction`, which should be above the horizon.
+    ///
+    /// `turbidity` is the haziness of the atmosphere, from 2 for a very clear sky to 10 for a
+    /// hazy one.
+    pub fn new(sun_direction: Vec3, turbidity: f64) -> Self {
+        let sun_direction = sun_direction.unit_vector();
+        let t = turbidity;
+        let perez = perez_coefficients(turbidity);
+
+        let theta_s = sun_direction.1.clamp(0.0, 1.0).acos();
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_s);
+        let zenith_luminance = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+        let chromaticity = |m: [[f64; 4]; 3]| {
+            let [t2, t1, t0] = m.map(|[a, b, c, d]| {
+                a * theta_s.powi(3) + b * theta_s.powi(2) + c * theta_s + d
+            });
+            t2 * t * t + t1 * t + t0
+        };
+        let zenith_x = chromaticity([
+            [0.00166, -0.00375, 0.00209, 0.0],
+            [-0.02903, 0.06377, -0.03202, 0.00394],
+            [0.11693, -0.21196, 0.06052, 0.25886],
+        ]);
+        let zenith_y = chromaticity([
+            [0.00275, -0.00610, 0.00317, 0.0],
+            [-0.04214, 0.08970, -0.04153, 0.00516],
+            [0.15346, -0.26756, 0.06670, 0.26688],
+        ]);
+        let zenith = [zenith_luminance, zenith_x, zenith_y];
+        let zenith = array::from_fn(|i| zenith[i] / perez[i].eval(1.0, theta_s));
+        let model = Preetham { sun_direction, perez, zenith };
+
+        let sun = (sun_direction.1 > 0.0).then(|| {
+            let radiance = SUN_LUMINANCE / UNIT_LUMINANCE * sun_color(turbidity, theta_s);
+            Sun::new(sun_direction, radiance, SUN_ANGULAR_RADIUS)
+        });
+
+        let image = Rgb32FImage::from_fn(TABLE_WIDTH, TABLE_HEIGHT, |i, j| {
+            let u = (i as f64 + 0.5) / TABLE_WIDTH as f64;
+            let v = (j as f64 + 0.5) / TABLE_HEIGHT as f64;
+            let Vec3(r, g, b) = model.radiance(equirect_direction(u, v));
+            image::Rgb([r as f32, g as f32, b as f32])
+        });
+        // pick the sun or the sky in proportion to how much light they give
+        let sky_power: f64 = image
+            .enumerate_pixels()
+            .map(|(_, j, pixel)| {
+                let [r, g, b] = pixel.0;
+                let sin_theta = (PI * (j as f64 + 0.5) / TABLE_HEIGHT as f64).sin();
+                Color::new(r.into(), g.into(), b.into()).luminance() * sin_theta
+            })
+            .sum::<f64>()
+            * (TAU * PI / (TABLE_WIDTH * TABLE_HEIGHT) as f64);
+        let sun_power = sun.as_ref().map_or(0.0, Sun::power);
+        let total_power = sun_power + sky_power;
+
+        Self {
+            model,
+            intensity: 1.0,
+            sun,
+            table: EnvironmentMap::new(image),
+            // nothing to choose between in a black sky
+            sun_probability: if total_power > 0.0 { sun_power / total_power } else { 0.0 },
+        }
+    }
+
+    /// Removes the sun, leaving only the light scattered by the sky.
+    pub fn without_sun(mut self) -> Self {
+        self.sun = None;
+        self.sun_probability = 0.0;
+        self
+    }
+
+    /// Scales the radiance of the sky and the sun.
+    pub fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    /// The sun disk in this sky, unless it is below the horizon or was removed.
+    pub fn sun(&self) -> Option<&Sun> {
+        self.sun.as_ref()
+    }
+}
+
+/// The sky part of [`Sky`].
+struct Preetham {
+    sun_direction: Vec3,
+    /// Perez distributions for the luminance Y and the chromaticities x and y.
+    perez: [Perez; 3],
+    /// Y, x and y at the zenith, divided by their Perez distribution at the zenith.
+    zenith: [f64; 3],
+}
+
+impl Preetham {
+    fn radiance(&self, direction: Vec3) -> Color {
+        let cos_theta = direction.1.max(0.001);
+        let gamma = direction.dot(self.sun_direction).clamp(-1.0, 1.0).acos();
+        let [luminance, x, y] =
+            array::from_fn(|i| self.zenith[i] * self.perez[i].eval(cos_theta, gamma));
+        let xyz = Color::new(x / y * luminance, luminance, (1.0 - x - y) / y * luminance);
+        let Vec3(r, g, b) = xyz_to_linear_srgb(xyz / UNIT_LUMINANCE);
+        Color::new(r.max(0.0), g.max(0.0), b.max(0.0))
+    }
+}
+
+impl Environment for Sky {
+    fn radiance(&self, direction: Vec3) -> Color {
+        let sun = self.sun.as_ref().map_or(Color::default(), |sun| sun.radiance(direction));
+        self.intensity * (self.model.radiance(direction) + sun)
+    }
+    fn sample(&self) -> Vec3 {
+        match &self.sun {
+            Some(sun) if random_double() < self.sun_probability => sun.sample(),
+            _ => self.table.sample(),
+        }
+    }
+    fn pdf(&self, direction: Vec3) -> f64 {
+        let sun = self.sun.as_ref().map_or(0.0, |sun| sun.pdf(direction));
+        self.sun_probability * sun + (1.0 - self.sun_probability) * self.table.pdf(direction)
+    }
+}
+
+/// A disk of constant radiance in the sky, like the sun.
+pub struct Sun {
+    direction: Vec3,
+    radiance: Color,
+    cos_theta_max: f64,
+}
+
+impl Sun {
+    /// A disk centered on `direction` with a radius of `angular_radius` degrees.
+    pub fn new(direction: Vec3, radiance: Color, angular_radius: f64) -> Self {
+        Self {
+            direction: direction.unit_vector(),
+            radiance,
+            cos_theta_max: angular_radius.to_radians().cos(),
+        }
+    }
+
+    pub fn direction(&self) -> Vec3 {
+        self.direction
+    }
+
+    fn solid_angle(&self) -> f64 {
+        TAU * (1.0 - self.cos_theta_max)
+    }
+
+    /// Luminous flux through a surface facing the sun.
+    fn power(&self) -> f64 {
+        self.radiance.luminance() * self.solid_angle()
+    }
+}
+
+impl Environment for Sun {
+    fn radiance(&self, direction: Vec3) -> Color {
+        if direction.dot(self.direction) >= self.cos_theta_max {
+            self.radiance
+        } else {
+            Color::default()
+        }
+    }
+    fn sample(&self) -> Vec3 {
+        let z = 1.0 - random_double() * (1.0 - self.cos_theta_max);
+        let phi = TAU * random_double();
+        let r = (1.0 - z * z).sqrt();
+        Onb::new(self.direction).transform(Vec3(phi.cos() * r, phi.sin() * r, z))
+    }
+    fn pdf(&self, direction: Vec3) -> f64 {
+        if direction.unit_vector().dot(self.direction) >= self.cos_theta_max {
+            self.solid_angle().recip()
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Color of the sun at `theta_s` from the zenith, using the transmittance of the atmosphere from
+/// the appendix of the Preetham paper without ozone, water vapor and mixed gases.
+///
+/// The color has a luminance of 1 outside the atmosphere.
+fn sun_color(turbidity: f64, theta_s: f64) -> Color {
+    // relative optical mass of air (Kasten's formula)
+    let air_mass = 1.0 / (theta_s.cos() + 0.15 * (93.885 - theta_s.to_degrees()).powf(-1.253));
+    // Ångström's turbidity coefficient
+    let beta = 0.04608 * turbidity - 0.04586;
+    let transmittance = |wavelength: f64| {
+        let micrometers = wavelength / 1000.0;
+        let rayleigh = 0.008735 * micrometers.powf(-4.08);
+        let aerosol = beta * micrometers.powf(-1.3);
+        (-air_mass * (rayleigh + aerosol)).exp()
+    };
+
+    let luminance = spectrum_to_xyz(|l| blackbody(l, SUN_TEMPERATURE)).1;
+    let xyz = spectrum_to_xyz(|l| blackbody(l, SUN_TEMPERATURE) * transmittance(l));
+    let Vec3(r, g, b) = xyz_to_linear_srgb(xyz / luminance);
+    Color::new(r.max(0.0), g.max(0.0), b.max(0.0))
+}
+
+/// Perez coefficients for Y, x and y, fitted as linear functions of the turbidity.
+#[rustfmt::skip]
+fn perez_coefficients(t: f64) -> [Perez; 3] {
+    [
+        [ 0.1787 * t - 1.4630, -0.3554 * t + 0.4275, -0.0227 * t + 5.3251,
+          0.1206 * t - 2.5771, -0.0670 * t + 0.3703],
+        [-0.0193 * t - 0.2592, -0.0665 * t + 0.0008, -0.0004 * t + 0.2125,
+         -0.0641 * t - 0.8989, -0.0033 * t + 0.0452],
+        [-0.0167 * t - 0.2608, -0.0950 * t + 0.0092, -0.0079 * t + 0.2102,
+         -0.0441 * t - 1.6537, -0.0109 * t + 0.0529],
+    ]
+    .map(Perez)
+}
@@ -0,0 +1,224 @@
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::environment::Environment;
+use crate::object::{Object, ObjectList};
+use crate::pdf::{EnvironmentPdf, MixturePdf, ObjectPdf, Pdf};
+use crate::vec3::{Point, Vec3};
+
+mod bvh;
+pub use bvh::{LightBounds, LightBvh};
+
+/// A light as seen from a point in the scene.
+pub struct LightSample {
+    /// Unit vector from the point towards the light.
+    pub direction: Vec3,
+    /// Distance to the light, infinite for lights that are infinitely far away.
+    pub distance: f64,
+    /// The light arriving at the point from `direction`.
+    pub radiance: Color,
+}
+
+/// A light that has no surface, so rays can never hit it and it only contributes through
+/// explicit sampling at each scattering point.
+///
+/// That sampling needs the material's [`scattering_pdf`](crate::material::Material), so only
+/// diffuse materials are lit by these. Materials that pick their own scattered ray (metals,
+/// glass, `Subsurface` and the coat of `Coated`) don't reflect them at all, not even as a
+/// highlight.
+pub trait Light: Send + Sync {
+    fn sample(&self, point: Point) -> LightSample;
+}
+
+/// A point light that shines equally in all directions.
+pub struct PointLight {
+    position: Point,
+    /// Radiant intensity, i.e. power per solid angle.
+    intensity: Color,
+}
+
+impl PointLight {
+    pub fn new(position: Point, intensity: impl Into<Color>) -> Self {
+        Self {
+            position,
+            intensity: intensity.into(),
+        }
+    }
+}
+
+impl Light for PointLight {
+    fn sample(&self, point: Point) -> LightSample {
+        let to_light = self.position - point;
+        let distance_squared = to_light.length_squared();
+        LightSample {
+            direction: to_light.unit_vector(),
+            distance: distance_squared.sqrt(),
+            radiance: self.intensity / distance_squared,
+        }
+    }
+}
+
+/// A point light that only shines in a cone, fading out towards its edge.
+pub struct SpotLight {
+    position: Point,
+    /// Unit vector along the center of the cone.
+    direction: Vec3,
+    intensity: Color,
+    cos_falloff_start: f64,
+    cos_total_width: f64,
+}
+
+impl SpotLight {
+    /// `total_width` is the angle between the center and the edge of the cone and
+    /// `falloff_start` the angle where it starts to fade out, both in degrees.
+    pub fn new(
+        position: Point,
+        look_at: Point,
+        intensity: impl Into<Color>,
+        total_width: f64,
+        falloff_start: f64,
+    ) -> Self {
+        Self {
+            position,
+            direction: (look_at - position).unit_vector(),
+            intensity: intensity.into(),
+            cos_falloff_start: falloff_start.min(total_width).to_radians().cos(),
+            cos_total_width: total_width.to_radians().cos(),
+        }
+    }
+
+    fn falloff(&self, cos_theta: f64) -> f64 {
+        if cos_theta >= self.cos_falloff_start {
+            return 1.0;
+        }
+        if cos_theta <= self.cos_total_width {
+            return 0.0;
+        }
+        let t = (cos_theta - self.cos_total_width)
+            / (self.cos_falloff_start - self.cos_total_width);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+impl Light for SpotLight {
+    fn sample(&self, point: Point) -> LightSample {
+        let to_light = self.position - point;
+        let distance_squared = to_light.length_squared();
+        let direction = to_light.unit_vector();
+        let falloff = self.falloff(-direction.dot(self.direction));
+        LightSample {
+            direction,
+            distance: distance_squared.sqrt(),
+            radiance: self.intensity * (falloff / distance_squared),
+        }
+    }
+}
+
+/// A light infinitely far away, like the sun.
+pub struct DirectionalLight {
+    /// Unit vector pointing towards the light.
+    to_light: Vec3,
+    /// Irradiance on a surface facing the light.
+    irradiance: Color,
+}
+
+impl DirectionalLight {
+    /// `direction` is the direction the light travels in.
+    pub fn new(direction: Vec3, irradiance: impl Into<Color>) -> Self {
+        Self {
+            to_light: -direction.unit_vector(),
+            irradiance: irradiance.into(),
+        }
+    }
+}
+
+impl Light for DirectionalLight {
+    fn sample(&self, _: Point) -> LightSample {
+        LightSample {
+            direction: self.to_light,
+            distance: f64::INFINITY,
+            radiance: self.irradiance,
+        }
+    }
+}
+
+/// How often to sample each of a few objects with the given powers. Objects that don't emit get
+/// the average power of those that do, and if none do they are all equally likely.
+pub(crate) fn sampling_weights(powers: &mut [f64]) {
+    let emitters: Vec<f64> = powers.iter().copied().filter(|&p| p > 0.0).collect();
+    let average = if emitters.is_empty() {
+        1.0
+    } else {
+        emitters.iter().sum::<f64>() / emitters.len() as f64
+    };
+    powers.iter_mut().filter(|p| **p <= 0.0).for_each(|p| *p = average);
+}
+
+/// All the lights of a scene.
+///
+/// Scenes without area lights get the emissive objects of their world, see [`Lights::discover`].
+/// Adding area lights by hand turns that off, e.g. to leave some emitters out or to sample
+/// objects that don't emit, like a glass sphere that focuses light. Those are sampled as often
+/// as an average emitter, see [`sampling_weights`].
+#[derive(Default)]
+pub struct Lights {
+    /// Emissive objects, sampled through [`Object::random`] and [`Object::pdf_value`]. These also
+    /// have to be in the world to be seen.
+    area: LightBvh,
+    delta: Vec<Box<dyn Light>>,
+}
+
+impl Lights {
+    pub fn add_area(&mut self, o: impl Object + 'static) {
+        self.area.add(o);
+    }
+
+    /// Adds a light without a surface. These only light diffuse materials, see [`Light`].
+    pub fn add(&mut self, light: impl Light + 'static) {
+        self.delta.push(Box::new(light));
+    }
+
+    /// Adds every object in `world` that emits light as an area light. Lists and BVHs are
+    /// searched through, while other objects, such as a [`Translate`](crate::object::Translate)
+    /// of a lamp, are added as a whole.
+    pub fn discover(&mut self, world: &ObjectList) {
+        fn visit(object: Arc<dyn Object>, area: &mut LightBvh) {
+            let children = object.children();
+            if !children.is_empty() {
+                children.into_iter().for_each(|child| visit(child, area));
+            } else if object.power() > 0.0 {
+                area.add_shared(object);
+            }
+        }
+        world.children().into_iter().for_each(|object| visit(object, &mut self.area));
+    }
+
+    /// The area lights, or `None` if there are none to sample.
+    pub fn area(&self) -> Option<&LightBvh> {
+        (!self.area.is_empty()).then_some(&self.area)
+    }
+
+    pub fn delta(&self) -> &[Box<dyn Light>] {
+        &self.delta
+    }
+
+    /// Mixes a material's pdf with one that samples the area lights as seen from `origin` and
+    /// the environment, if there is one.
+    pub fn mixed_pdf<'a>(
+        &'a self,
+        origin: Point,
+        scattering: Box<dyn Pdf>,
+        environment: Option<&'a dyn Environment>,
+    ) -> Box<dyn Pdf + 'a> {
+        let light: Box<dyn Pdf + 'a> = match (self.area(), environment) {
+            (Some(area), Some(env)) => Box::new(MixturePdf::new(
+                ObjectPdf::new(area, origin),
+                EnvironmentPdf(env),
+            )),
+            (Some(area), None) => Box::new(ObjectPdf::new(area, origin)),
+            (None, Some(env)) => Box::new(EnvironmentPdf(env)),
+            (None, None) => return scattering,
+        };
+        Box::new(MixturePdf::new(light, scattering))
+    }
+}
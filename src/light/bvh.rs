@@ -0,0 +1,397 @@
+use std::f64::consts::PI;
+use std::sync::{Arc, OnceLock};
+
+use crate::aabb::AxisAlignedBoundingBox;
+use crate::interval::Interval;
+use crate::object::{HitRecord, Object};
+use crate::ray::Ray;
+use crate::utils::random_double;
+use crate::vec3::{Point, Vec3};
+
+/// Where an emitter is, how much it emits and in which directions, for deciding how much it
+/// can light a point without looking at the emitter itself. From Conty Estevez and Kulla,
+/// "Importance Sampling of Many Lights with Adaptive Tree Splitting" (2018), as in pbrt-v4.
+#[derive(Clone, Copy)]
+pub struct LightBounds {
+    bounds: AxisAlignedBoundingBox,
+    power: f64,
+    /// Center of the cone that contains the normals of the emitter.
+    axis: Vec3,
+    /// Cosine of the half angle of the cone of normals.
+    cos_theta_o: f64,
+    /// Cosine of how far from its normal each point of the emitter emits.
+    cos_theta_e: f64,
+    two_sided: bool,
+}
+
+impl LightBounds {
+    /// An emitter that may emit in any direction from anywhere in `bounds`, like a sphere.
+    pub fn omnidirectional(bounds: AxisAlignedBoundingBox, power: f64) -> Self {
+        Self {
+            bounds,
+            power,
+            axis: Vec3(0.0, 0.0, 1.0),
+            cos_theta_o: -1.0,
+            cos_theta_e: 0.0,
+            two_sided: false,
+        }
+    }
+
+    /// A flat emitter that only emits into the hemisphere around `normal`, or also behind it if
+    /// it is `two_sided`.
+    pub fn flat(bounds: AxisAlignedBoundingBox, power: f64, normal: Vec3, two_sided: bool) -> Self {
+        Self {
+            bounds,
+            power,
+            axis: normal.unit_vector(),
+            cos_theta_o: 1.0,
+            cos_theta_e: 0.0,
+            two_sided,
+        }
+    }
+
+    pub fn power(&self) -> f64 {
+        self.power
+    }
+
+    /// The bounds of a copy of the emitter that was moved to fit in `bounds` and turned with
+    /// `rotate`.
+    pub fn transformed(
+        self,
+        bounds: AxisAlignedBoundingBox,
+        rotate: impl Fn(Vec3) -> Vec3,
+    ) -> Self {
+        Self {
+            bounds,
+            axis: rotate(self.axis),
+            ..self
+        }
+    }
+
+    pub fn merge(self, other: Self) -> Self {
+        let (axis, cos_theta_o) =
+            cone_union((self.axis, self.cos_theta_o), (other.axis, other.cos_theta_o));
+        Self {
+            bounds: self.bounds.merge(other.bounds),
+            power: self.power + other.power,
+            axis,
+            cos_theta_o,
+            cos_theta_e: self.cos_theta_e.min(other.cos_theta_e),
+            two_sided: self.two_sided || other.two_sided,
+        }
+    }
+
+    /// A conservative estimate of how much light this can give to `point`, which is 0 if none
+    /// of the emitter faces it.
+    fn importance(&self, point: Point) -> f64 {
+        let Self { bounds, .. } = *self;
+        let min = Point::new(bounds.x.min, bounds.y.min, bounds.z.min);
+        let max = Point::new(bounds.x.max, bounds.y.max, bounds.z.max);
+        let center = 0.5 * (min + max);
+        let radius = 0.5 * (max - min).length();
+
+        let to_point = point - center;
+        let distance_squared = to_point.length_squared();
+        // don't let the importance blow up close to the emitter
+        let clamped_distance_squared = distance_squared.max(radius);
+        if to_point.near_zero() {
+            return self.power / clamped_distance_squared;
+        }
+
+        // angle between the axis and the point
+        let mut cos_theta_w = self.axis.dot(to_point.unit_vector());
+        if self.two_sided {
+            cos_theta_w = cos_theta_w.abs();
+        }
+        let sin_theta_w = sin_from_cos(cos_theta_w);
+        // half angle of the bounds seen from the point
+        let cos_theta_b = if distance_squared < radius * radius {
+            -1.0
+        } else {
+            (1.0 - radius * radius / distance_squared).max(0.0).sqrt()
+        };
+        let sin_theta_o = sin_from_cos(self.cos_theta_o);
+
+        // the smallest angle to the point of any normal in the cone, then of any point in the
+        // bounds
+        let cos_theta_x = cos_sub_clamped(sin_theta_w, cos_theta_w, sin_theta_o, self.cos_theta_o);
+        let sin_theta_x = sin_from_cos(cos_theta_x);
+        let cos_theta_p =
+            cos_sub_clamped(sin_theta_x, cos_theta_x, sin_from_cos(cos_theta_b), cos_theta_b);
+        if cos_theta_p <= self.cos_theta_e {
+            return 0.0;
+        }
+        self.power * cos_theta_p / clamped_distance_squared
+    }
+}
+
+fn sin_from_cos(cos: f64) -> f64 {
+    (1.0 - cos * cos).max(0.0).sqrt()
+}
+
+/// cos(max(0, a - b)) from the sines and cosines of `a` and `b`.
+fn cos_sub_clamped(sin_a: f64, cos_a: f64, sin_b: f64, cos_b: f64) -> f64 {
+    if cos_a > cos_b {
+        1.0
+    } else {
+        cos_a * cos_b + sin_a * sin_b
+    }
+}
+
+/// The smallest cone that contains both cones, given as their axis and cosine of half angle.
+fn cone_union((a, cos_a): (Vec3, f64), (b, cos_b): (Vec3, f64)) -> (Vec3, f64) {
+    let theta_a = cos_a.clamp(-1.0, 1.0).acos();
+    let theta_b = cos_b.clamp(-1.0, 1.0).acos();
+    let theta_d = a.dot(b).clamp(-1.0, 1.0).acos();
+    if (theta_d + theta_b).min(PI) <= theta_a {
+        return (a, cos_a);
+    }
+    if (theta_d + theta_a).min(PI) <= theta_b {
+        return (b, cos_b);
+    }
+
+    let theta_o = (theta_a + theta_d + theta_b) / 2.0;
+    let rotation_axis = a.cross(b);
+    if theta_o >= PI || rotation_axis.near_zero() {
+        return (a, -1.0);
+    }
+    // rotate `a` towards `b` until the cone touches the far sides of both (Rodrigues' formula)
+    let k = rotation_axis.unit_vector();
+    let (sin_r, cos_r) = (theta_o - theta_a).sin_cos();
+    let axis = a * cos_r + k.cross(a) * sin_r + k * k.dot(a) * (1.0 - cos_r);
+    (axis, theta_o.cos())
+}
+
+enum Node {
+    Leaf {
+        light: Arc<dyn Object>,
+        bounds: LightBounds,
+    },
+    Interior {
+        left: Box<Node>,
+        right: Box<Node>,
+        bounds: LightBounds,
+    },
+}
+
+impl Node {
+    fn build(mut lights: Vec<(Arc<dyn Object>, LightBounds)>) -> Node {
+        if lights.len() == 1 {
+            let (light, bounds) = lights.pop().unwrap();
+            return Node::Leaf { light, bounds };
+        }
+        let centroid = |b: &LightBounds| {
+            let AxisAlignedBoundingBox { x, y, z } = b.bounds;
+            Point::new(x.min + x.max, y.min + y.max, z.min + z.max) / 2.0
+        };
+        let centroids = lights
+            .iter()
+            .map(|(_, b)| AxisAlignedBoundingBox::from_points(centroid(b), centroid(b)))
+            .fold(AxisAlignedBoundingBox::EMPTY, AxisAlignedBoundingBox::merge);
+        let axis = centroids.longest_axis();
+        lights.sort_by(|(_, a), (_, b)| centroid(a)[axis].total_cmp(&centroid(b)[axis]));
+
+        let right = lights.split_off(lights.len() / 2);
+        let left = Node::build(lights);
+        let right = Node::build(right);
+        let bounds = left.bounds().merge(right.bounds());
+        Node::Interior {
+            left: Box::new(left),
+            right: Box::new(right),
+            bounds,
+        }
+    }
+
+    fn bounds(&self) -> LightBounds {
+        match self {
+            Node::Leaf { bounds, .. } | Node::Interior { bounds, .. } => *bounds,
+        }
+    }
+
+    /// Probability of going to the left child when sampling from `point`. Children that are
+    /// equally unimportant are picked evenly, so that sampling never fails.
+    fn left_probability(left: &Node, right: &Node, point: Point) -> f64 {
+        let left = left.bounds().importance(point);
+        let right = right.bounds().importance(point);
+        if left + right > 0.0 {
+            left / (left + right)
+        } else {
+            0.5
+        }
+    }
+
+    fn pdf_value(&self, origin: Point, direction: Vec3) -> f64 {
+        match self {
+            Node::Leaf { light, .. } => light.pdf_value(origin, direction),
+            Node::Interior { left, right, .. } => {
+                let p_left = Node::left_probability(left, right, origin);
+                // lights only have a density in directions that hit them, so children that the
+                // ray misses can be skipped
+                let ray = Ray { origin, direction };
+                [(left, p_left), (right, 1.0 - p_left)]
+                    .into_iter()
+                    .filter(|(child, p)| {
+                        let t = Interval::new(0.001, f64::INFINITY);
+                        *p > 0.0 && child.bounds().bounds.hit(ray, t)
+                    })
+                    .map(|(child, p)| p * child.pdf_value(origin, direction))
+                    .sum()
+            }
+        }
+    }
+
+    fn random(&self, origin: Point) -> Vec3 {
+        let mut node = self;
+        loop {
+            match node {
+                Node::Leaf { light, .. } => return light.random(origin),
+                Node::Interior { left, right, .. } => {
+                    let p_left = Node::left_probability(left, right, origin);
+                    node = if random_double() < p_left { left } else { right };
+                }
+            }
+        }
+    }
+}
+
+/// Area lights in a bounding volume hierarchy that picks lights by how much they can light
+/// the point being shaded, which helps scenes with many emitters where most are far away or
+/// facing elsewhere.
+///
+/// Each light's [`Object::pdf_value`] has to be zero for rays that miss its bounding box.
+#[derive(Default)]
+pub struct LightBvh {
+    lights: Vec<Arc<dyn Object>>,
+    aabb: AxisAlignedBoundingBox,
+    /// Built on first use, `None` if there are no lights.
+    root: OnceLock<Option<Node>>,
+}
+
+impl LightBvh {
+    pub fn add(&mut self, o: impl Object + 'static) {
+        self.add_shared(Arc::new(o));
+    }
+
+    /// Adds an object that is also in the world without copying it.
+    pub fn add_shared(&mut self, o: Arc<dyn Object>) {
+        self.aabb = self.aabb.merge(o.bounding_box());
+        self.lights.push(o);
+        self.root = OnceLock::new();
+    }
+
+    pub fn len(&self) -> usize {
+        self.lights.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lights.is_empty()
+    }
+
+    fn root(&self) -> Option<&Node> {
+        self.root
+            .get_or_init(|| {
+                let mut lights: Vec<_> = self
+                    .lights
+                    .iter()
+                    .map(|light| (light.clone(), light.light_bounds()))
+                    .collect();
+                let mut powers: Vec<f64> = lights.iter().map(|(_, b)| b.power).collect();
+                super::sampling_weights(&mut powers);
+                lights.iter_mut().zip(powers).for_each(|((_, b), power)| b.power = power);
+                (!lights.is_empty()).then(|| Node::build(lights))
+            })
+            .as_ref()
+    }
+}
+
+impl Object for LightBvh {
+    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        let mut closest = ray_t.max;
+        let mut hit_record = None;
+        for light in &self.lights {
+            if let Some(record) = light.hit(r, Interval::new(ray_t.min, closest)) {
+                closest = record.t;
+                hit_record = Some(record);
+            }
+        }
+        hit_record
+    }
+    fn bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.aabb
+    }
+    fn pdf_value(&self, origin: Point, direction: Vec3) -> f64 {
+        self.root().map_or(0.0, |root| root.pdf_value(origin, direction))
+    }
+    fn random(&self, origin: Point) -> Vec3 {
+        self.root().map_or(Vec3(1.0, 0.0, 0.0), |root| root.random(origin))
+    }
+    fn power(&self) -> f64 {
+        self.lights.iter().map(|light| light.power()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::DiffuseLight;
+    use crate::object::Quad;
+
+    #[test]
+    fn cone_union_contains_both() {
+        let angle = |a: Vec3, b: Vec3| a.dot(b).clamp(-1.0, 1.0).acos();
+        let cones = [
+            (Vec3(0.0, 0.0, 1.0), 1.0),
+            (Vec3(0.0, 0.0, 1.0), 0.5),
+            (Vec3(1.0, 0.0, 0.0), 0.9),
+            (Vec3(0.0, 0.6, -0.8), 0.0),
+            (Vec3(0.0, 0.0, -1.0), 1.0),
+            (Vec3(-1.0, 1.0, 1.0).unit_vector(), -0.3),
+        ];
+        for a in cones {
+            for b in cones {
+                let (axis, cos_theta) = cone_union(a, b);
+                let theta = cos_theta.acos();
+                for (cone_axis, cos) in [a, b] {
+                    let reach = angle(axis, cone_axis) + cos.acos();
+                    let contained = cos_theta == -1.0 || reach <= theta + 1e-9;
+                    assert!(contained, "{reach} > {theta}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pdf_matches_random() {
+        // in front of, to the right of and high above the origin, all facing it
+        let quad = |q, u, v| Quad::new(q, u, v, DiffuseLight::new((4.0, 4.0, 4.0)));
+        let quads = [
+            quad(Point::new(-0.5, -0.5, -2.0), Vec3(1.0, 0.0, 0.0), Vec3(0.0, 1.0, 0.0)),
+            quad(Point::new(3.0, -0.5, -0.5), Vec3(0.0, 0.0, 1.0), Vec3(0.0, 1.0, 0.0)),
+            quad(Point::new(-1.0, 5.0, -1.0), Vec3(2.0, 0.0, 0.0), Vec3(0.0, 0.0, 2.0)),
+        ];
+        let mut bvh = LightBvh::default();
+        for quad in &quads {
+            bvh.add(quad.clone());
+        }
+
+        // the pdf of the BVH is the probability of picking a light times its own pdf
+        let origin = Point::new(0.0, 0.0, 0.0);
+        let n = 20_000;
+        let mut counts = [0; 3];
+        let mut probabilities = [0.0; 3];
+        for _ in 0..n {
+            let direction = bvh.random(origin);
+            let ray = Ray { origin, direction };
+            let t = Interval::new(0.001, f64::INFINITY);
+            let i = quads.iter().position(|q| q.hit(ray, t).is_some()).unwrap();
+            counts[i] += 1;
+            probabilities[i] =
+                bvh.pdf_value(origin, direction) / quads[i].pdf_value(origin, direction);
+        }
+        for (count, probability) in counts.into_iter().zip(probabilities) {
+            let frequency = count as f64 / n as f64;
+            assert!((frequency - probability).abs() < 0.02, "{frequency} != {probability}");
+        }
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+}
@@ -2,7 +2,10 @@ pub mod aabb;
 pub mod bvh;
 pub mod camera;
 mod color;
+mod distribution;
+pub mod environment;
 mod interval;
+pub mod light;
 pub mod material;
 pub mod object;
 mod ray;
@@ -11,6 +14,8 @@ mod utils;
 pub(crate) mod vec3;
 mod onb;
 mod pdf;
+mod spectrum;
+pub mod texture;
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
@@ -1,12 +1,19 @@
-use std::f64::consts::FRAC_1_PI;
+use std::f64::consts::{FRAC_1_PI, PI};
+use std::sync::Arc;
 
 use crate::color::Color;
 use crate::object::HitRecord;
+use crate::onb::Onb;
 use crate::pdf::{CosinePdf, Pdf, SpherePdf};
 use crate::ray::Ray;
+use crate::spectrum::blackbody_color;
+use crate::texture::Texture;
 use crate::utils::random_double;
 use crate::vec3::{Point, Vec3};
 
+mod thin_film;
+pub use thin_film::{Substrate, ThinFilm};
+
 pub struct ScatterRecord {
     pub attenuation: Color,
     /// TODO: i'd like this to be an enum
@@ -17,7 +24,6 @@ pub struct ScatterRecord {
 
 pub trait Material {
     #[expect(unused_variables)]
-    // TODO: no uv because we skipped textures
     fn emitted(&self, r_in: &Ray, rec: &HitRecord, p: Point) -> Color {
         Color::default()
     }
@@ -26,11 +32,40 @@ pub trait Material {
     fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
         0.0
     }
+    /// Like `scatter`, for a path of a single wavelength in nanometers. Only dispersive materials
+    /// need to override this.
+    #[expect(unused_variables)]
+    fn scatter_wavelength(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        wavelength: f64,
+    ) -> Option<ScatterRecord> {
+        self.scatter(r_in, rec)
+    }
+    /// Whether the scattered direction depends on the wavelength.
+    fn is_dispersive(&self) -> bool {
+        false
+    }
+    /// Luminance-weighted power emitted by a surface of `area` made of this material, which
+    /// decides how often it is sampled among other lights.
+    #[expect(unused_variables)]
+    fn emitted_power(&self, area: f64) -> f64 {
+        0.0
+    }
+    /// Whether this emits from the back face as well as the front.
+    fn is_two_sided(&self) -> bool {
+        false
+    }
+    /// Called by objects with the area of their surface, for materials that spread a given
+    /// power over it.
+    #[expect(unused_variables)]
+    fn set_area(&mut self, area: f64) {}
 }
 
 macro_rules! generate_any_material {
     ($($x:ident),*$(,)?) => {
-        #[derive(Clone, Copy)]
+        #[derive(Clone)]
         pub enum AnyMaterial {
             $($x($x),)*
         }
@@ -51,6 +86,36 @@ macro_rules! generate_any_material {
                     $(Self::$x(v) => v.scattering_pdf(r_in, rec, scattered),)*
                 }
             }
+            fn scatter_wavelength(
+                &self,
+                r_in: &Ray,
+                rec: &HitRecord,
+                wavelength: f64,
+            ) -> Option<ScatterRecord> {
+                match self {
+                    $(Self::$x(v) => v.scatter_wavelength(r_in, rec, wavelength),)*
+                }
+            }
+            fn is_dispersive(&self) -> bool {
+                match self {
+                    $(Self::$x(v) => v.is_dispersive(),)*
+                }
+            }
+            fn emitted_power(&self, area: f64) -> f64 {
+                match self {
+                    $(Self::$x(v) => v.emitted_power(area),)*
+                }
+            }
+            fn is_two_sided(&self) -> bool {
+                match self {
+                    $(Self::$x(v) => v.is_two_sided(),)*
+                }
+            }
+            fn set_area(&mut self, area: f64) {
+                match self {
+                    $(Self::$x(v) => v.set_area(area),)*
+                }
+            }
         }
 
         $(impl From<$x> for AnyMaterial {
@@ -61,7 +126,17 @@ macro_rules! generate_any_material {
     };
 }
 
-generate_any_material!(Lambertian, Metal, Dielectric, DiffuseLight, DummyMaterial);
+generate_any_material!(
+    Lambertian,
+    OrenNayar,
+    Metal,
+    Dielectric,
+    Coated,
+    NormalMapped,
+    Subsurface,
+    DiffuseLight,
+    DummyMaterial,
+);
 
 #[derive(Clone, Copy)]
 pub struct DummyMaterial;
@@ -99,10 +174,73 @@ impl Material for Lambertian {
     }
 }
 
+/// Rough diffuse reflector using the qualitative Oren–Nayar model.
+///
+/// With `sigma == 0` this is identical to [`Lambertian`].
+#[derive(Clone, Copy)]
+pub struct OrenNayar {
+    albedo: Color,
+    a: f64,
+    b: f64,
+}
+
+impl OrenNayar {
+    /// `sigma` is the standard deviation of the microfacet slope angle, in degrees.
+    pub fn new(albedo: impl Into<Color>, sigma: f64) -> Self {
+        let sigma = sigma.to_radians();
+        let sigma2 = sigma * sigma;
+        OrenNayar {
+            albedo: albedo.into(),
+            a: 1.0 - sigma2 / (2.0 * (sigma2 + 0.33)),
+            b: 0.45 * sigma2 / (sigma2 + 0.09),
+        }
+    }
+}
+
+impl Material for OrenNayar {
+    fn scatter(&self, _: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        Some(ScatterRecord {
+            attenuation: self.albedo,
+            pdf: Box::new(CosinePdf::new(rec.normal)),
+            skip_pdf: None,
+        })
+    }
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        let wo = -r_in.direction.unit_vector();
+        let wi = scattered.direction.unit_vector();
+        let cos_i = rec.normal.dot(wi);
+        if cos_i <= 0.0 {
+            return 0.0;
+        }
+        let cos_o = rec.normal.dot(wo).clamp(0.0, 1.0);
+        let sin_i = (1.0 - cos_i * cos_i).max(0.0).sqrt();
+        let sin_o = (1.0 - cos_o * cos_o).sqrt();
+
+        // cos(phi_i - phi_o), from the projections of both directions onto the tangent plane
+        let max_cos = if sin_i > 1e-4 && sin_o > 1e-4 {
+            let tangent_i = wi - cos_i * rec.normal;
+            let tangent_o = wo - cos_o * rec.normal;
+            (tangent_i.dot(tangent_o) / (sin_i * sin_o)).max(0.0)
+        } else {
+            0.0
+        };
+
+        // alpha = max(theta_i, theta_o), beta = min(theta_i, theta_o)
+        let (sin_alpha, tan_beta) = if cos_i > cos_o {
+            (sin_o, sin_i / cos_i)
+        } else {
+            (sin_i, sin_o / cos_o.max(1e-8))
+        };
+
+        cos_i * FRAC_1_PI * (self.a + self.b * max_cos * sin_alpha * tan_beta)
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct Metal {
     albedo: Color,
     fuzziness: f64,
+    thin_film: Option<ThinFilm>,
 }
 
 impl Metal {
@@ -110,8 +248,15 @@ impl Metal {
         Metal {
             albedo: albedo.into(),
             fuzziness: fuzziness.min(1.0),
+            thin_film: None,
         }
     }
+
+    /// Coats the metal with a thin film, like oil or the oxide layer on tempered steel.
+    pub fn with_thin_film(mut self, thin_film: ThinFilm) -> Self {
+        self.thin_film = Some(thin_film);
+        self
+    }
 }
 
 impl Material for Metal {
@@ -124,9 +269,18 @@ impl Material for Metal {
                 origin: rec.point,
                 direction: reflected,
             };
-            
+
+            let attenuation = match self.thin_film {
+                Some(film) => {
+                    let cos_theta = (-r_in.direction.unit_vector()).dot(rec.normal);
+                    let Vec3(r, g, b) = self.albedo;
+                    let substrate = [r, g, b].map(Substrate::Conductor);
+                    film.reflectance(cos_theta, 1.0, substrate)
+                }
+                None => self.albedo,
+            };
             Some(ScatterRecord {
-                attenuation: self.albedo,
+                attenuation,
                 pdf: Box::new(SpherePdf),
                 skip_pdf: Some(scattered),
             })
@@ -136,14 +290,66 @@ impl Material for Metal {
     }
 }
 
+/// Index of refraction of a dielectric as a function of wavelength.
+#[derive(Clone, Copy)]
+pub enum RefractiveIndex {
+    Constant(f64),
+    /// `n = a + b / λ²`, with λ in micrometers.
+    Cauchy { a: f64, b: f64 },
+    /// `n² = 1 + Σ b λ² / (λ² - c)`, with λ in micrometers. Glass catalogs list these.
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl RefractiveIndex {
+    /// The wavelength (in nm) of the sodium D line, where catalogs quote a single index.
+    pub const D_LINE: f64 = 587.6;
+
+    pub fn at(self, wavelength: f64) -> f64 {
+        let l = wavelength / 1000.0;
+        let l2 = l * l;
+        match self {
+            RefractiveIndex::Constant(n) => n,
+            RefractiveIndex::Cauchy { a, b } => a + b / l2,
+            RefractiveIndex::Sellmeier { b, c } => {
+                let sum: f64 = b.iter().zip(c).map(|(b, c)| b * l2 / (l2 - c)).sum();
+                (1.0 + sum).sqrt()
+            }
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct Dielectric {
-    refraction_index: f64,
+    refraction_index: RefractiveIndex,
+    thin_film: Option<ThinFilm>,
 }
 
 impl Dielectric {
     pub fn new(refraction_index: f64) -> Self {
-        Self { refraction_index }
+        Self::with_index(RefractiveIndex::Constant(refraction_index))
+    }
+
+    /// A dielectric that disperses light when rendered in spectral mode. In RGB mode the index
+    /// at [`RefractiveIndex::D_LINE`] is used.
+    pub fn with_index(refraction_index: RefractiveIndex) -> Self {
+        Self {
+            refraction_index,
+            thin_film: None,
+        }
+    }
+
+    pub fn cauchy(a: f64, b: f64) -> Self {
+        Self::with_index(RefractiveIndex::Cauchy { a, b })
+    }
+
+    pub fn sellmeier(b: [f64; 3], c: [f64; 3]) -> Self {
+        Self::with_index(RefractiveIndex::Sellmeier { b, c })
+    }
+
+    /// Coats the surface with a thin film. A soap bubble is a film over a `Dielectric::new(1.0)`.
+    pub fn with_thin_film(mut self, thin_film: ThinFilm) -> Self {
+        self.thin_film = Some(thin_film);
+        self
     }
 
     fn reflectance(refraction_index: f64, cosine: f64) -> f64 {
@@ -151,14 +357,17 @@ impl Dielectric {
         let r0 = r0 * r0;
         r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
     }
-}
 
-impl Material for Dielectric {
-    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+    fn scatter_with_index(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        refraction_index: f64,
+    ) -> Option<ScatterRecord> {
         let ri = if rec.front_face {
-            self.refraction_index.recip()
+            refraction_index.recip()
         } else {
-            self.refraction_index
+            refraction_index
         };
 
         let unit_direction = r_in.direction.unit_vector();
@@ -167,7 +376,28 @@ impl Material for Dielectric {
 
         let cannot_refact = ri * sin_theta > 1.0;
 
-        let direction = if cannot_refact || Self::reflectance(ri, cos_theta) > random_double() {
+        let (attenuation, reflect) = match self.thin_film {
+            _ if cannot_refact => (Color::splat(1.), true),
+            None => (Color::splat(1.), Self::reflectance(ri, cos_theta) > random_double()),
+            Some(film) => {
+                // the reflectance differs per channel, so pick with the average and reweight.
+                let (outside, inside) = if rec.front_face {
+                    (1.0, refraction_index)
+                } else {
+                    (refraction_index, 1.0)
+                };
+                let substrate = [Substrate::Dielectric(inside); 3];
+                let reflectance = film.reflectance(cos_theta, outside, substrate);
+                let p = reflectance.into_iter().sum::<f64>() / 3.0;
+                if p > random_double() {
+                    (reflectance / p, true)
+                } else {
+                    ((Color::splat(1.) - reflectance) / (1.0 - p), false)
+                }
+            }
+        };
+
+        let direction = if reflect {
             unit_direction.reflect(rec.normal)
         } else {
             unit_direction.refract(rec.normal, ri)
@@ -178,25 +408,498 @@ impl Material for Dielectric {
             direction,
         };
         Some(ScatterRecord {
-            attenuation: Color::splat(1.),
+            attenuation,
             pdf: Box::new(SpherePdf),
             skip_pdf: Some(ray),
         })
     }
 }
 
+impl Material for Dielectric {
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        let refraction_index = self.refraction_index.at(RefractiveIndex::D_LINE);
+        self.scatter_with_index(r_in, rec, refraction_index)
+    }
+    fn scatter_wavelength(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        wavelength: f64,
+    ) -> Option<ScatterRecord> {
+        self.scatter_with_index(r_in, rec, self.refraction_index.at(wavelength))
+    }
+    fn is_dispersive(&self) -> bool {
+        !matches!(self.refraction_index, RefractiveIndex::Constant(_))
+    }
+}
+
+/// A thin clear dielectric layer (clearcoat, varnish) over another material.
+///
+/// Each scatter either reflects off the coat or passes through to the base, chosen with the
+/// Fresnel reflectance of the coat, so the choice probability and the weight cancel.
+#[derive(Clone)]
+pub struct Coated {
+    base: Arc<AnyMaterial>,
+    refraction_index: f64,
+    roughness: f64,
+}
+
+impl Coated {
+    pub fn new(base: impl Into<AnyMaterial>, refraction_index: f64, roughness: f64) -> Self {
+        Self {
+            base: Arc::new(base.into()),
+            refraction_index,
+            roughness: roughness.clamp(0.0, 1.0),
+        }
+    }
+
+    fn scatter_with(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        base_scatter: impl FnOnce() -> Option<ScatterRecord>,
+    ) -> Option<ScatterRecord> {
+        let unit_direction = r_in.direction.unit_vector();
+        let cos_theta = (-unit_direction).dot(rec.normal).min(1.0);
+
+        if Dielectric::reflectance(self.refraction_index, cos_theta) <= random_double() {
+            return base_scatter();
+        }
+
+        let reflected = unit_direction.reflect(rec.normal);
+        let reflected = reflected + self.roughness * Vec3::random_unit_vector();
+        if reflected.dot(rec.normal) <= 0.0 {
+            return None;
+        }
+        Some(ScatterRecord {
+            attenuation: Color::splat(1.),
+            pdf: Box::new(SpherePdf),
+            skip_pdf: Some(Ray {
+                origin: rec.point,
+                direction: reflected,
+            }),
+        })
+    }
+}
+
+impl Material for Coated {
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord, p: Point) -> Color {
+        self.base.emitted(r_in, rec, p)
+    }
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        self.scatter_with(r_in, rec, || self.base.scatter(r_in, rec))
+    }
+    /// Only consulted when the base was picked, so this is just the base's pdf.
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        self.base.scattering_pdf(r_in, rec, scattered)
+    }
+    fn scatter_wavelength(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        wavelength: f64,
+    ) -> Option<ScatterRecord> {
+        self.scatter_with(r_in, rec, || {
+            self.base.scatter_wavelength(r_in, rec, wavelength)
+        })
+    }
+    fn is_dispersive(&self) -> bool {
+        self.base.is_dispersive()
+    }
+    fn emitted_power(&self, area: f64) -> f64 {
+        self.base.emitted_power(area)
+    }
+    fn is_two_sided(&self) -> bool {
+        self.base.is_two_sided()
+    }
+    fn set_area(&mut self, area: f64) {
+        Arc::make_mut(&mut self.base).set_area(area);
+    }
+}
+
+/// A translucent material like wax, skin or marble: a dielectric boundary around a medium that
+/// scatters and absorbs light.
+///
+/// Light is followed on a random walk inside the object. Each time a ray hits the boundary from
+/// the inside, a free-flight distance is sampled to see whether it scattered before getting
+/// there. If it did, the walk continues from that point in a random direction, otherwise the ray
+/// leaves (or is reflected back) through the boundary. The object must be closed.
 #[derive(Clone, Copy)]
-pub struct DiffuseLight(pub Color);
+pub struct Subsurface {
+    refraction_index: f64,
+    /// Absorption coefficient per unit of distance, for each channel.
+    absorption: Color,
+    /// Scattering coefficient per unit of distance, for each channel.
+    scattering: Color,
+}
 
-impl Material for DiffuseLight {
-    fn emitted(&self, _r_in: &Ray, rec: &HitRecord, _: Point) -> Color {
+impl Subsurface {
+    pub fn new(
+        refraction_index: f64,
+        absorption: impl Into<Color>,
+        scattering: impl Into<Color>,
+    ) -> Self {
+        Self {
+            refraction_index,
+            absorption: absorption.into(),
+            scattering: scattering.into(),
+        }
+    }
+
+    /// A medium with the given single scattering albedo, where light travels `mean_free_path`
+    /// between interactions on average.
+    pub fn from_albedo(
+        refraction_index: f64,
+        albedo: impl Into<Color>,
+        mean_free_path: f64,
+    ) -> Self {
+        let albedo = albedo.into();
+        let extinction = mean_free_path.recip();
+        Self::new(
+            refraction_index,
+            (Color::splat(1.0) - albedo) * extinction,
+            albedo * extinction,
+        )
+    }
+}
+
+impl Material for Subsurface {
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        let mut weight = Color::splat(1.0);
         if !rec.front_face {
+            // The ray went `distance` through the medium. Sample the distance with one channel's
+            // extinction and weight with the average pdf over all channels.
+            let extinction = self.absorption + self.scattering;
+            let distance = rec.t * r_in.direction.length();
+            let channel = ((random_double() * 3.0) as usize).min(2);
+            let flight = -(1.0 - random_double()).ln() / extinction[channel];
+            let transmittance = |d: f64| {
+                let Vec3(r, g, b) = extinction;
+                Color::new((-r * d).exp(), (-g * d).exp(), (-b * d).exp())
+            };
+            let average = |c: Color| c.into_iter().sum::<f64>() / 3.0;
+
+            if flight < distance {
+                let transmittance = transmittance(flight);
+                let pdf = average(extinction * transmittance);
+                let direction = r_in.direction.unit_vector();
+                return Some(ScatterRecord {
+                    attenuation: self.scattering * transmittance / pdf,
+                    pdf: Box::new(SpherePdf),
+                    skip_pdf: Some(Ray {
+                        origin: r_in.origin + flight * direction,
+                        direction: Vec3::random_unit_vector(),
+                    }),
+                });
+            }
+
+            let transmittance = transmittance(distance);
+            weight = transmittance / average(transmittance);
+        }
+
+        let mut srec = Dielectric::new(self.refraction_index).scatter(r_in, rec)?;
+        srec.attenuation = srec.attenuation * weight;
+        Some(srec)
+    }
+}
+
+/// How [`NormalMapped`] perturbs the shading normal.
+#[derive(Clone)]
+pub enum NormalPerturbation {
+    /// A tangent space normal map, with red along the tangent, green along the bitangent and
+    /// blue along the normal. Load it with `ImageTexture::load_linear`.
+    NormalMap(Arc<dyn Texture>),
+    /// A height field read from the average of the texture's channels. `strength` scales its
+    /// slopes.
+    Bump {
+        height: Arc<dyn Texture>,
+        strength: f64,
+    },
+}
+
+/// Shades a material with a normal that is perturbed by a normal map or a bump map.
+///
+/// The geometric normal still decides which side of the surface rays end up on, so that a
+/// perturbed normal can never make light leak through the surface.
+#[derive(Clone)]
+pub struct NormalMapped {
+    base: Arc<AnyMaterial>,
+    perturbation: NormalPerturbation,
+}
+
+impl NormalMapped {
+    pub fn normal_map(base: impl Into<AnyMaterial>, map: Arc<dyn Texture>) -> Self {
+        Self {
+            base: Arc::new(base.into()),
+            perturbation: NormalPerturbation::NormalMap(map),
+        }
+    }
+
+    pub fn bump(base: impl Into<AnyMaterial>, height: Arc<dyn Texture>, strength: f64) -> Self {
+        Self {
+            base: Arc::new(base.into()),
+            perturbation: NormalPerturbation::Bump { height, strength },
+        }
+    }
+
+    /// The hit record with the normal replaced by the shading normal.
+    fn shading_record<'a>(&self, r_in: &Ray, rec: &HitRecord<'a>) -> HitRecord<'a> {
+        let frame = Onb::from_normal_tangent(rec.normal, rec.tangent);
+        // keep the frame right handed with respect to the outward normal on back faces
+        let bitangent = if rec.front_face { frame.v() } else { -frame.v() };
+
+        let normal = match &self.perturbation {
+            NormalPerturbation::NormalMap(map) => {
+                let Vec3(x, y, z) = map.value(rec.u, rec.v, rec.point) * 2.0 - Color::splat(1.0);
+                x * frame.u() + y * bitangent + z * frame.w()
+            }
+            NormalPerturbation::Bump { height, strength } => {
+                const DELTA: f64 = 1e-3;
+                let h = |u, v| height.value(u, v, rec.point).into_iter().sum::<f64>() / 3.0;
+                let h0 = h(rec.u, rec.v);
+                let dh_du = (h(rec.u + DELTA, rec.v) - h0) / DELTA;
+                let dh_dv = (h(rec.u, rec.v + DELTA) - h0) / DELTA;
+                frame.w() - *strength * (dh_du * frame.u() + dh_dv * bitangent)
+            }
+        };
+
+        // a normal facing away from the viewer would shade nothing, tilt it back towards them.
+        let wo = -r_in.direction.unit_vector();
+        let mut normal = normal.unit_vector();
+        let cos_o = wo.dot(normal);
+        if cos_o < 0.01 {
+            normal = (normal + (0.01 - cos_o) * wo).unit_vector();
+        }
+
+        HitRecord { normal, ..*rec }
+    }
+
+    /// Whether `direction` is on the same side of both the geometric and the shading surface.
+    fn is_consistent(rec: &HitRecord, shading: &HitRecord, direction: Vec3) -> bool {
+        (direction.dot(rec.normal) > 0.0) == (direction.dot(shading.normal) > 0.0)
+    }
+
+    fn check(
+        rec: &HitRecord,
+        shading: &HitRecord,
+        srec: Option<ScatterRecord>,
+    ) -> Option<ScatterRecord> {
+        let srec = srec?;
+        match srec.skip_pdf {
+            Some(ray) if !Self::is_consistent(rec, shading, ray.direction) => None,
+            _ => Some(srec),
+        }
+    }
+}
+
+impl Material for NormalMapped {
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord, p: Point) -> Color {
+        self.base.emitted(r_in, &self.shading_record(r_in, rec), p)
+    }
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        let shading = self.shading_record(r_in, rec);
+        Self::check(rec, &shading, self.base.scatter(r_in, &shading))
+    }
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        let shading = self.shading_record(r_in, rec);
+        if !Self::is_consistent(rec, &shading, scattered.direction) {
+            return 0.0;
+        }
+        self.base.scattering_pdf(r_in, &shading, scattered)
+    }
+    fn scatter_wavelength(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        wavelength: f64,
+    ) -> Option<ScatterRecord> {
+        let shading = self.shading_record(r_in, rec);
+        let srec = self.base.scatter_wavelength(r_in, &shading, wavelength);
+        Self::check(rec, &shading, srec)
+    }
+    fn is_dispersive(&self) -> bool {
+        self.base.is_dispersive()
+    }
+    fn emitted_power(&self, area: f64) -> f64 {
+        self.base.emitted_power(area)
+    }
+    fn is_two_sided(&self) -> bool {
+        self.base.is_two_sided()
+    }
+    fn set_area(&mut self, area: f64) {
+        Arc::make_mut(&mut self.base).set_area(area);
+    }
+}
+
+/// An emitter that sends out the same radiance in every direction.
+#[derive(Clone)]
+pub struct DiffuseLight {
+    emit: Arc<dyn Texture>,
+    /// Luminance of `emit` averaged over the surface, for [`Material::emitted_power`].
+    average_luminance: f64,
+    intensity: f64,
+    /// The total power in watts, set by [`DiffuseLight::with_power`]. Takes the place of
+    /// `intensity`.
+    power: Option<f64>,
+    /// The area of the object this is on, which `power` is spread over.
+    area: f64,
+    two_sided: bool,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: impl Into<Color>) -> Self {
+        Self::textured(Arc::new(emit.into()))
+    }
+
+    /// A white light of the color of a black body at `kelvin`, with a luminance of 1.
+    pub fn from_temperature(kelvin: f64) -> Self {
+        Self::new(blackbody_color(kelvin))
+    }
+
+    /// Emits the texture's color, like a screen or a sign.
+    pub fn textured(emit: Arc<dyn Texture>) -> Self {
+        Self {
+            average_luminance: emit.average().luminance(),
+            emit,
+            intensity: 1.0,
+            power: None,
+            area: 0.0,
+            two_sided: false,
+        }
+    }
+
+    /// Also emits from the back face. Otherwise the back is black.
+    pub fn two_sided(mut self) -> Self {
+        self.two_sided = true;
+        self
+    }
+
+    /// Multiplies the emitted radiance by `intensity`.
+    pub fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self.power = None;
+        self
+    }
+
+    /// Scales the emission so that the object this ends up on emits `watts` in total over its
+    /// area (in scene units), whatever its color, which then only sets the tint. A white light
+    /// of 1 W/m² over one side has a radiance of 1/π.
+    ///
+    /// This counts the back face if the light is [`two_sided`](Self::two_sided), whether that is
+    /// set before or after.
+    pub fn with_power(mut self, watts: f64) -> Self {
+        self.power = Some(watts);
+        self
+    }
+
+    fn sides(&self) -> f64 {
+        if self.two_sided { 2.0 } else { 1.0 }
+    }
+
+    /// What the texture is multiplied by.
+    fn scale(&self) -> f64 {
+        match self.power {
+            // a black texture (or a surface with no area) emits nothing however much it is scaled
+            Some(watts) if self.average_luminance > 0.0 && self.area > 0.0 => {
+                watts / (PI * self.area * self.sides() * self.average_luminance)
+            }
+            Some(_) => 0.0,
+            None => self.intensity,
+        }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn emitted(&self, _r_in: &Ray, rec: &HitRecord, p: Point) -> Color {
+        if !rec.front_face && !self.two_sided {
             Color::splat(0.)
         } else {
-            self.0
+            self.scale() * self.emit.value(rec.u, rec.v, p)
         }
     }
     fn scatter(&self, _: &Ray, _: &HitRecord) -> Option<ScatterRecord> {
         None
     }
+    fn emitted_power(&self, area: f64) -> f64 {
+        PI * area * self.sides() * self.scale() * self.average_luminance
+    }
+    fn is_two_sided(&self) -> bool {
+        self.two_sided
+    }
+    fn set_area(&mut self, area: f64) {
+        self.area = area;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use super::*;
+    use crate::interval::Interval;
+    use crate::object::{Object, Quad};
+
+    /// Rays from all around that hit a horizontal quad at the origin, from above and below.
+    fn rays() -> impl Iterator<Item = Ray> {
+        (0..12).flat_map(|i| {
+            [-1.0, 1.0].into_iter().flat_map(move |side| {
+                [0.2, 0.6, 1.0, 3.0].map(|height| {
+                    let (sin, cos) = (i as f64 * TAU / 12.0).sin_cos();
+                    let origin = Point::new(cos, side * height, sin);
+                    Ray { origin, direction: -origin }
+                })
+            })
+        })
+    }
+
+    fn hit<'a>(quad: &'a Quad, r: Ray) -> HitRecord<'a> {
+        quad.hit(r, Interval::new(0.001, f64::INFINITY)).unwrap()
+    }
+
+    fn quad(material: impl Into<AnyMaterial>) -> Quad {
+        let (u, v) = (Vec3(0.0, 0.0, 2.0), Vec3(2.0, 0.0, 0.0));
+        Quad::new(Point::new(-1.0, 0.0, -1.0), u, v, material)
+    }
+
+    #[test]
+    fn flat_normal_map_keeps_normal() {
+        let flat = Arc::new(Color::new(0.5, 0.5, 1.0));
+        let material = NormalMapped::normal_map(Lambertian::new((0.5, 0.5, 0.5)), flat);
+        let quad = quad(material.clone());
+        for r in rays() {
+            let rec = hit(&quad, r);
+            let shading = material.shading_record(&r, &rec);
+            assert!((shading.normal - rec.normal).length() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn scattering_stays_above_surface() {
+        // tilted far over along the tangent
+        let steep: Arc<dyn Texture> = Arc::new(Color::new(0.95, 0.5, 0.6));
+        let material = NormalMapped::normal_map(Metal::new((0.9, 0.9, 0.9), 0.0), steep.clone());
+        let quad = quad(material.clone());
+        let mut rejected = 0;
+        for r in rays() {
+            let rec = hit(&quad, r);
+            let shading = material.shading_record(&r, &rec);
+            assert!((-r.direction.unit_vector()).dot(shading.normal) > 0.0);
+
+            match material.scatter(&r, &rec) {
+                Some(ScatterRecord { skip_pdf: Some(scattered), .. }) => {
+                    assert!(scattered.direction.dot(rec.normal) > 0.0);
+                }
+                _ => rejected += 1,
+            }
+        }
+        // the steep normal does reflect some rays into the surface, which are dropped
+        assert!(rejected > 0);
+
+        let material = NormalMapped::normal_map(Lambertian::new((0.5, 0.5, 0.5)), steep);
+        for r in rays() {
+            let rec = hit(&quad, r);
+            let below = Ray { origin: rec.point, direction: -rec.normal };
+            assert_eq!(material.scattering_pdf(&r, &rec, &below), 0.0);
+        }
+    }
 }
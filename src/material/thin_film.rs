@@ -0,0 +1,163 @@
+use std::array;
+use std::f64::consts::PI;
+
+use crate::color::Color;
+
+/// Wavelength bands (in nm) that are averaged to get the red, green and blue reflectances.
+const RGB_BANDS: [(f64, f64); 3] = [(600.0, 700.0), (500.0, 600.0), (400.0, 500.0)];
+const SAMPLES_PER_BAND: usize = 8;
+
+/// A thin transparent film on top of a surface, like soap or oil, that causes interference colors.
+#[derive(Clone, Copy)]
+pub struct ThinFilm {
+    /// Thickness of the film in nanometers.
+    pub thickness: f64,
+    pub refraction_index: f64,
+}
+
+/// Amplitude of the reflection off the surface under the film.
+#[derive(Clone, Copy)]
+pub enum Substrate {
+    /// A dielectric with this index of refraction.
+    Dielectric(f64),
+    /// A conductor that reflects this fraction of the light. This approximates the complex
+    /// index of a real metal with a real amplitude and a phase flip.
+    Conductor(f64),
+}
+
+impl ThinFilm {
+    pub const fn new(thickness: f64, refraction_index: f64) -> Self {
+        Self {
+            thickness,
+            refraction_index,
+        }
+    }
+
+    /// Reflectance at a single wavelength (in nm) for light arriving from a medium with index
+    /// `outside` at an angle with cosine `cos_theta` to the normal.
+    ///
+    /// This is the Airy summation of all the internal reflections in the film, averaged over
+    /// s and p polarization.
+    pub fn reflectance_at(
+        &self,
+        wavelength: f64,
+        cos_theta: f64,
+        outside: f64,
+        substrate: Substrate,
+    ) -> f64 {
+        let n1 = outside;
+        let n2 = self.refraction_index;
+        let cos1 = cos_theta.clamp(0.0, 1.0);
+        let sin1_sq = 1.0 - cos1 * cos1;
+
+        let sin2_sq = sin1_sq * (n1 / n2) * (n1 / n2);
+        if sin2_sq >= 1.0 {
+            return 1.0;
+        }
+        let cos2 = (1.0 - sin2_sq).sqrt();
+
+        let [r23_s, r23_p] = match substrate {
+            Substrate::Dielectric(n3) => {
+                let sin3_sq = sin1_sq * (n1 / n3) * (n1 / n3);
+                if sin3_sq >= 1.0 {
+                    return 1.0;
+                }
+                let cos3 = (1.0 - sin3_sq).sqrt();
+                [fresnel_s(n2, cos2, n3, cos3), fresnel_p(n2, cos2, n3, cos3)]
+            }
+            Substrate::Conductor(reflectance) => [-reflectance.sqrt(); 2],
+        };
+        let r12_s = fresnel_s(n1, cos1, n2, cos2);
+        let r12_p = fresnel_p(n1, cos1, n2, cos2);
+
+        // phase difference between two successive reflections out of the film
+        let delta = 4.0 * PI * n2 * self.thickness * cos2 / wavelength;
+        let cos_delta = delta.cos();
+        let airy = |r12: f64, r23: f64| {
+            let cross = 2.0 * r12 * r23 * cos_delta;
+            (r12 * r12 + r23 * r23 + cross) / (1.0 + r12 * r12 * r23 * r23 + cross)
+        };
+        (0.5 * (airy(r12_s, r23_s) + airy(r12_p, r23_p))).clamp(0.0, 1.0)
+    }
+
+    /// Reflectance for the red, green and blue channels, each averaged over its wavelength band.
+    /// `substrate` is given per channel so that colored metals can be described.
+    pub fn reflectance(&self, cos_theta: f64, outside: f64, substrate: [Substrate; 3]) -> Color {
+        let [r, g, b] = array::from_fn(|i| {
+            let (lo, hi) = RGB_BANDS[i];
+            let step = (hi - lo) / SAMPLES_PER_BAND as f64;
+            let sum: f64 = (0..SAMPLES_PER_BAND)
+                .map(|k| lo + (k as f64 + 0.5) * step)
+                .map(|wavelength| self.reflectance_at(wavelength, cos_theta, outside, substrate[i]))
+                .sum();
+            sum / SAMPLES_PER_BAND as f64
+        });
+        Color::new(r, g, b)
+    }
+}
+
+fn fresnel_s(ni: f64, cos_i: f64, nt: f64, cos_t: f64) -> f64 {
+    (ni * cos_i - nt * cos_t) / (ni * cos_i + nt * cos_t)
+}
+
+fn fresnel_p(ni: f64, cos_i: f64, nt: f64, cos_t: f64) -> f64 {
+    (nt * cos_i - ni * cos_t) / (nt * cos_i + ni * cos_t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unpolarized Fresnel reflectance of the interface between `n1` and `n2`.
+    fn fresnel(cos1: f64, n1: f64, n2: f64) -> f64 {
+        let sin2_sq = (1.0 - cos1 * cos1) * (n1 / n2) * (n1 / n2);
+        let cos2 = (1.0 - sin2_sq).sqrt();
+        let (s, p) = (fresnel_s(n1, cos1, n2, cos2), fresnel_p(n1, cos1, n2, cos2));
+        0.5 * (s * s + p * p)
+    }
+
+    #[test]
+    fn no_thickness_is_bare_surface() {
+        for cos_theta in [1.0, 0.8, 0.5, 0.2, 0.05] {
+            for wavelength in [400.0, 550.0, 700.0] {
+                for film_index in [1.2, 1.33, 1.8] {
+                    let film = ThinFilm::new(0.0, film_index);
+                    let substrate = Substrate::Dielectric(1.5);
+                    let r = film.reflectance_at(wavelength, cos_theta, 1.0, substrate);
+                    let bare = fresnel(cos_theta, 1.0, 1.5);
+                    assert!((r - bare).abs() < 1e-9, "{r} != {bare}");
+                }
+                // a film of the outside medium is no film at all, on a conductor too
+                let film = ThinFilm::new(0.0, 1.0);
+                let substrate = Substrate::Conductor(0.6);
+                let r = film.reflectance_at(wavelength, cos_theta, 1.0, substrate);
+                assert!((r - 0.6).abs() < 1e-9, "{r} != 0.6");
+            }
+        }
+    }
+
+    #[test]
+    fn reflectance_in_unit_interval() {
+        let substrates = [
+            Substrate::Dielectric(1.0),
+            Substrate::Dielectric(1.5),
+            Substrate::Dielectric(2.4),
+            Substrate::Conductor(0.0),
+            Substrate::Conductor(0.95),
+        ];
+        let films = [
+            ThinFilm::new(50.0, 1.33),
+            ThinFilm::new(300.0, 2.0),
+            ThinFilm::new(1000.0, 1.5),
+        ];
+        for (film, substrate) in films.into_iter().flat_map(|f| substrates.map(|s| (f, s))) {
+            for cos_theta in (0..=20).map(|i| i as f64 / 20.0) {
+                // this also catches NaN
+                for wavelength in (380..=780).step_by(10).map(f64::from) {
+                    let r = film.reflectance_at(wavelength, cos_theta, 1.0, substrate);
+                    assert!((0.0..=1.0).contains(&r), "{r} at {wavelength}nm");
+                }
+            }
+        }
+    }
+}
@@ -1,34 +1,43 @@
-use std::f64::consts::TAU;
+use std::f64::consts::{PI, TAU};
 use std::mem::take;
-
-use rand::thread_rng;
+use std::sync::{Arc, OnceLock};
 
 use crate::aabb::AxisAlignedBoundingBox;
 use crate::bvh::BvhNode;
+use crate::distribution::AliasTable;
 use crate::interval::Interval;
-use crate::material::AnyMaterial;
+use crate::light::{sampling_weights, LightBounds};
+use crate::material::{AnyMaterial, Material};
 use crate::onb::Onb;
 use crate::ray::Ray;
+use crate::texture::Texture;
 use crate::utils::random_double;
 use crate::vec3::{Point, Vec3};
 
 pub mod polyhedra;
 
 #[derive(Clone, Copy)]
-pub struct HitRecord {
+pub struct HitRecord<'a> {
     pub point: Point,
     pub normal: Vec3,
     pub t: f64,
+    /// Surface coordinates of the hit, for textures. Objects that have them set these after
+    /// construction.
+    pub u: f64,
+    pub v: f64,
+    /// Direction in which `u` increases along the surface, or zero if the object has no uv
+    /// coordinates. Used as the tangent for normal mapping.
+    pub tangent: Vec3,
     pub front_face: bool,
-    pub material: AnyMaterial,
+    pub material: &'a AnyMaterial,
 }
 
-impl HitRecord {
+impl<'a> HitRecord<'a> {
     pub fn new(
         r: Ray,
         t: f64,
         outward_normal: impl FnOnce(Point) -> Vec3,
-        material: impl Into<AnyMaterial>,
+        material: &'a AnyMaterial,
     ) -> Self {
         let point = r.at(t);
         let outward_normal = outward_normal(point);
@@ -42,22 +51,37 @@ impl HitRecord {
             point,
             normal,
             t,
+            u: 0.0,
+            v: 0.0,
+            tangent: Vec3::default(),
             front_face,
-            material: material.into(),
+            material,
         }
     }
 }
 
 pub trait Object: Send + Sync {
-    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord>;
+    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord<'_>>;
     fn bounding_box(&self) -> AxisAlignedBoundingBox;
     // todo implement more of these
     fn pdf_value(&self, _origin: Point, _direction: Vec3) -> f64 { 0.0 }
     fn random(&self, _origin: Point) -> Vec3 { Vec3(1.0, 0.0, 0.0) }
+    /// Luminance-weighted power emitted by this object, which decides how often
+    /// [`ObjectList::random`] picks it among other lights. Objects that can't be sampled as
+    /// lights leave this at 0.
+    fn power(&self) -> f64 { 0.0 }
+    /// Where and in which directions this emits, for the [`LightBvh`](crate::light::LightBvh).
+    /// By default it may emit in any direction from anywhere in its bounding box.
+    fn light_bounds(&self) -> LightBounds {
+        LightBounds::omnidirectional(self.bounding_box(), self.power())
+    }
+    /// The objects this is a collection of, which are searched for lights one by one. Other
+    /// objects have none and are added to the lights as a whole if they emit.
+    fn children(&self) -> Vec<Arc<dyn Object>> { Vec::new() }
 }
 
 impl<T: Object + ?Sized> Object for &T {
-    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord> {
+    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
         T::hit(*self, r, ray_t)
     }
     fn bounding_box(&self) -> AxisAlignedBoundingBox {
@@ -69,12 +93,21 @@ impl<T: Object + ?Sized> Object for &T {
     fn random(&self, origin: Point) -> Vec3 {
         T::random(*self, origin)
     }
+    fn power(&self) -> f64 {
+        T::power(*self)
+    }
+    fn light_bounds(&self) -> LightBounds {
+        T::light_bounds(*self)
+    }
+    fn children(&self) -> Vec<Arc<dyn Object>> {
+        T::children(*self)
+    }
 }
 
 pub struct DummyObject;
 
 impl Object for DummyObject {
-    fn hit(&self, _: Ray, _: Interval) -> Option<HitRecord> {
+    fn hit(&self, _: Ray, _: Interval) -> Option<HitRecord<'_>> {
         None
     }
     fn bounding_box(&self) -> AxisAlignedBoundingBox {
@@ -101,7 +134,7 @@ impl<T: Object> Translate<T> {
 }
 
 impl<T: Object> Object for Translate<T> {
-    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord> {
+    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
         let offset_r = Ray {
             origin: r.origin - self.offset,
             direction: r.direction,
@@ -115,6 +148,18 @@ impl<T: Object> Object for Translate<T> {
     fn bounding_box(&self) -> AxisAlignedBoundingBox {
         self.bbox
     }
+    fn pdf_value(&self, origin: Point, direction: Vec3) -> f64 {
+        self.object.pdf_value(origin - self.offset, direction)
+    }
+    fn random(&self, origin: Point) -> Vec3 {
+        self.object.random(origin - self.offset)
+    }
+    fn power(&self) -> f64 {
+        self.object.power()
+    }
+    fn light_bounds(&self) -> LightBounds {
+        self.object.light_bounds().transformed(self.bbox, |v| v)
+    }
 }
 
 pub struct RotateY<T> {
@@ -164,7 +209,7 @@ impl<T: Object> RotateY<T> {
 
 impl<T: Object> Object for RotateY<T> {
     #[rustfmt::skip]
-    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord> {
+    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
         let Ray { mut origin, mut direction } = r;
         origin.0 = self.cos_theta*r.origin.0 - self.sin_theta*r.origin.2;
         origin.2 = self.sin_theta*r.origin.0 + self.cos_theta*r.origin.2;
@@ -174,7 +219,7 @@ impl<T: Object> Object for RotateY<T> {
 
         let rotated_r = Ray { origin, direction };
 
-        let Some(mut rec) = self.object.hit(rotated_r, ray_t) else { return None; };
+        let mut rec = self.object.hit(rotated_r, ray_t)?;
 
         let mut p = rec.point;
         p.0 =  self.cos_theta*rec.point.0 + self.sin_theta*rec.point.2;
@@ -184,16 +229,90 @@ impl<T: Object> Object for RotateY<T> {
         normal.0 =  self.cos_theta*rec.normal.0 + self.sin_theta*rec.normal.2;
         normal.2 = -self.sin_theta*rec.normal.0 + self.cos_theta*rec.normal.2;
 
+        let mut tangent = rec.tangent;
+        tangent.0 =  self.cos_theta*rec.tangent.0 + self.sin_theta*rec.tangent.2;
+        tangent.2 = -self.sin_theta*rec.tangent.0 + self.cos_theta*rec.tangent.2;
+
         rec.point = p;
         rec.normal = normal;
+        rec.tangent = tangent;
         Some(rec)
     }
     fn bounding_box(&self) -> AxisAlignedBoundingBox {
         self.bbox
     }
+    fn pdf_value(&self, origin: Point, direction: Vec3) -> f64 {
+        // solid angle doesn't change with rotation
+        self.object.pdf_value(self.to_object(origin), self.to_object(direction))
+    }
+    fn random(&self, origin: Point) -> Vec3 {
+        self.to_world(self.object.random(self.to_object(origin)))
+    }
+    fn power(&self) -> f64 {
+        self.object.power()
+    }
+    fn light_bounds(&self) -> LightBounds {
+        self.object.light_bounds().transformed(self.bbox, |v| self.to_world(v))
+    }
 }
 
-#[derive(Clone, Copy)]
+impl<T> RotateY<T> {
+    fn to_object(&self, Vec3(x, y, z): Vec3) -> Vec3 {
+        Vec3(self.cos_theta * x - self.sin_theta * z, y, self.sin_theta * x + self.cos_theta * z)
+    }
+    fn to_world(&self, Vec3(x, y, z): Vec3) -> Vec3 {
+        Vec3(self.cos_theta * x + self.sin_theta * z, y, -self.sin_theta * x + self.cos_theta * z)
+    }
+}
+
+/// Cuts out the parts of an object where the alpha of `mask` is below `cutoff`, for foliage,
+/// fences and the like. Rays pass through cut out parts as if nothing was there.
+pub struct AlphaMask<T> {
+    object: T,
+    mask: Arc<dyn Texture>,
+    cutoff: f64,
+}
+
+impl<T: Object> AlphaMask<T> {
+    pub fn new(object: T, mask: Arc<dyn Texture>, cutoff: f64) -> Self {
+        Self {
+            object,
+            mask,
+            cutoff,
+        }
+    }
+}
+
+impl<T: Object> Object for AlphaMask<T> {
+    fn hit(&self, r: Ray, mut ray_t: Interval) -> Option<HitRecord<'_>> {
+        loop {
+            let rec = self.object.hit(r, ray_t)?;
+            if self.mask.alpha(rec.u, rec.v, rec.point) >= self.cutoff {
+                return Some(rec);
+            }
+            ray_t.min = rec.t.next_up();
+        }
+    }
+    fn bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.object.bounding_box()
+    }
+    // Sampling ignores the mask. Directions through holes then just find whatever is behind,
+    // which is still unbiased as long as `random` and `pdf_value` agree with each other.
+    fn pdf_value(&self, origin: Point, direction: Vec3) -> f64 {
+        self.object.pdf_value(origin, direction)
+    }
+    fn random(&self, origin: Point) -> Vec3 {
+        self.object.random(origin)
+    }
+    fn power(&self) -> f64 {
+        self.object.power()
+    }
+    fn light_bounds(&self) -> LightBounds {
+        self.object.light_bounds()
+    }
+}
+
+#[derive(Clone)]
 pub struct Sphere {
     center: Point,
     radius: f64,
@@ -205,30 +324,38 @@ impl Sphere {
     pub fn new(center: Point, radius: f64, material: impl Into<AnyMaterial>) -> Sphere {
         let rvec = Vec3::splat(radius);
         let aabb = AxisAlignedBoundingBox::from_points(center - rvec, center + rvec);
+        let mut material = material.into();
+        material.set_area(2.0 * TAU * radius * radius);
         Sphere {
             center,
             radius,
-            material: material.into(),
+            material,
             aabb,
         }
     }
+    /// `u` goes around the Y axis starting from -X, `v` goes from the bottom to the top.
+    fn uv(outward_normal: Vec3) -> (f64, f64) {
+        let theta = (-outward_normal.1).acos();
+        let phi = f64::atan2(-outward_normal.2, outward_normal.0) + PI;
+        (phi / TAU, theta / PI)
+    }
     fn random_to_sphere(radius: f64, distance_squared: f64) -> Vec3 {
         let r1 = random_double();
         let r2 = random_double();
         let z = 1. + r2*((1.-radius*radius/distance_squared).sqrt() - 1.);
         let phi = TAU * r1;
-        let x = phi.cos() - (1.-z*z).sqrt();
+        let x = phi.cos()*(1.-z*z).sqrt();
         let y = phi.sin()*(1.-z*z).sqrt();
         Vec3(x, y, z)
     }
 }
 
 impl Object for Sphere {
-    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord> {
+    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
         let Sphere {
             center,
             radius,
-            material,
+            ref material,
             aabb: _,
         } = *self;
         let oc = center - r.origin;
@@ -252,12 +379,12 @@ impl Object for Sphere {
             }
         }
 
-        Some(HitRecord::new(
-            r,
-            root,
-            |point| (point - center) / radius,
-            material,
-        ))
+        let mut rec = HitRecord::new(r, root, |point| (point - center) / radius, material);
+        let outward_normal = (rec.point - center) / radius;
+        (rec.u, rec.v) = Self::uv(outward_normal);
+        // d(point)/du, zero at the poles
+        rec.tangent = Vec3(outward_normal.2, 0.0, -outward_normal.0);
+        Some(rec)
     }
     fn bounding_box(&self) -> AxisAlignedBoundingBox {
         self.aabb
@@ -279,9 +406,13 @@ impl Object for Sphere {
         let uvw = Onb::new(direction);
         uvw.transform(Self::random_to_sphere(self.radius, distance_squared))
     }
+
+    fn power(&self) -> f64 {
+        self.material.emitted_power(2.0 * TAU * self.radius * self.radius)
+    }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Triangle {
     a: Point,
     b: Point,
@@ -294,19 +425,21 @@ impl Triangle {
     pub fn new(a: Point, b: Point, c: Point, material: impl Into<AnyMaterial>) -> Triangle {
         let aabb1 = AxisAlignedBoundingBox::from_points(a, b);
         let aabb2 = AxisAlignedBoundingBox::from_points(b, c);
-        Triangle {
+        let mut triangle = Triangle {
             a,
             b,
             c,
             material: material.into(),
             aabb: aabb1.merge(aabb2),
-        }
+        };
+        triangle.material.set_area(triangle.area());
+        triangle
     }
 }
 
 impl Object for Triangle {
     // https://en.wikipedia.org/wiki/M%C3%B6ller%E2%80%93Trumbore_intersection_algorithm
-    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord> {
+    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
         let e1 = self.b - self.a;
         let e2 = self.c - self.a;
 
@@ -335,7 +468,11 @@ impl Object for Triangle {
         let t = inv_det * e2.dot(s_cross_e1);
 
         if ray_t.contains(t) {
-            Some(HitRecord::new(r, t, |_| e1.cross(e2), self.material))
+            let mut rec = HitRecord::new(r, t, |_| e1.cross(e2).unit_vector(), &self.material);
+            // barycentric coordinates, i.e. the weights of `b` and `c`
+            (rec.u, rec.v) = (u, v);
+            rec.tangent = e1;
+            Some(rec)
         } else {
             None
         }
@@ -343,9 +480,42 @@ impl Object for Triangle {
     fn bounding_box(&self) -> AxisAlignedBoundingBox {
         self.aabb
     }
+    fn pdf_value(&self, origin: Point, direction: Vec3) -> f64 {
+        let Some(rec) = self.hit(Ray { origin, direction }, Interval::new(0.001, f64::INFINITY))
+        else {
+            return 0.;
+        };
+
+        let distance_squared = rec.t * rec.t * direction.length_squared();
+        let cosine = (direction.dot(rec.normal) / direction.length()).abs();
+
+        distance_squared / (cosine * self.area())
+    }
+    fn random(&self, origin: Point) -> Vec3 {
+        // uniform over the area, folding the far half of the parallelogram back in
+        let (mut u, mut v) = (random_double(), random_double());
+        if u + v > 1.0 {
+            (u, v) = (1.0 - u, 1.0 - v);
+        }
+        let p = self.a + u * (self.b - self.a) + v * (self.c - self.a);
+        p - origin
+    }
+    fn power(&self) -> f64 {
+        self.material.emitted_power(self.area())
+    }
+    fn light_bounds(&self) -> LightBounds {
+        let normal = (self.b - self.a).cross(self.c - self.a);
+        LightBounds::flat(self.aabb, self.power(), normal, self.material.is_two_sided())
+    }
 }
 
-#[derive(Clone, Copy)]
+impl Triangle {
+    fn area(&self) -> f64 {
+        0.5 * (self.b - self.a).cross(self.c - self.a).length()
+    }
+}
+
+#[derive(Clone)]
 pub struct Quad {
     q: Point,
     u: Vec3,
@@ -366,12 +536,14 @@ impl Quad {
         let normal = n.unit_vector();
         let d = normal.dot(q);
         let w = n / n.dot(n);
+        let mut mat = mat.into();
+        mat.set_area(n.length());
         Self {
             q,
             u,
             v,
             w,
-            mat: mat.into(),
+            mat,
             bbox: bbox1.merge(bbox2),
             normal,
             d,
@@ -379,20 +551,27 @@ impl Quad {
         }
     }
 
-    pub fn hit_as_interior(&self, a: f64, b: f64, rec: HitRecord) -> Option<HitRecord> {
+    pub fn hit_as_interior<'a>(
+        &self,
+        a: f64,
+        b: f64,
+        mut rec: HitRecord<'a>,
+    ) -> Option<HitRecord<'a>> {
         let unit_interval = Interval::new(0.0, 1.0);
 
         if !unit_interval.contains(a) || !unit_interval.contains(b) {
             None
         } else {
-            // TODO uv coords should be set here.
+            rec.u = a;
+            rec.v = b;
+            rec.tangent = self.u;
             Some(rec)
         }
     }
 }
 
 impl Object for Quad {
-    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord> {
+    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
         let denom = self.normal.dot(r.direction);
 
         if denom.abs() < 1e-8 {
@@ -407,7 +586,7 @@ impl Object for Quad {
         let planar_hitpt_vector = r.at(t) - self.q;
         let alpha = self.w.dot(planar_hitpt_vector.cross(self.v));
         let beta = self.w.dot(self.u.cross(planar_hitpt_vector));
-        let rec = HitRecord::new(r, t, |_| self.normal, self.mat);
+        let rec = HitRecord::new(r, t, |_| self.normal, &self.mat);
         self.hit_as_interior(alpha, beta, rec)
     }
     fn bounding_box(&self) -> AxisAlignedBoundingBox {
@@ -428,6 +607,12 @@ impl Object for Quad {
         let p = self.q + (random_double() * self.u) + (random_double() * self.v);
         p - origin
     }
+    fn power(&self) -> f64 {
+        self.mat.emitted_power(self.area)
+    }
+    fn light_bounds(&self) -> LightBounds {
+        LightBounds::flat(self.bbox, self.power(), self.normal, self.mat.is_two_sided())
+    }
 }
 
 #[rustfmt::skip]
@@ -443,11 +628,11 @@ pub fn box_3d(a: Point, b: Point, mat: impl Into<AnyMaterial>) -> ObjectList {
 
     let mut list = ObjectList::default();
     let faces = [   
-        Quad::new(Point::new(min.0, min.1, max.2),  dx,  dy, mat), // front
-        Quad::new(Point::new(max.0, min.1, max.2), -dz,  dy, mat), // right
-        Quad::new(Point::new(max.0, min.1, min.2), -dx,  dy, mat), // back
-        Quad::new(Point::new(min.0, min.1, min.2),  dz,  dy, mat), // left
-        Quad::new(Point::new(min.0, max.1, max.2),  dx, -dz, mat), // top
+        Quad::new(Point::new(min.0, min.1, max.2),  dx,  dy, mat.clone()), // front
+        Quad::new(Point::new(max.0, min.1, max.2), -dz,  dy, mat.clone()), // right
+        Quad::new(Point::new(max.0, min.1, min.2), -dx,  dy, mat.clone()), // back
+        Quad::new(Point::new(min.0, min.1, min.2),  dz,  dy, mat.clone()), // left
+        Quad::new(Point::new(min.0, max.1, max.2),  dx, -dz, mat.clone()), // top
         Quad::new(Point::new(min.0, min.1, min.2),  dx,  dz, mat), // bottom
     ];
     list.add_all(faces);
@@ -456,14 +641,17 @@ pub fn box_3d(a: Point, b: Point, mat: impl Into<AnyMaterial>) -> ObjectList {
 
 #[derive(Default)]
 pub struct ObjectList {
-    objects: Vec<Box<dyn Object>>,
+    objects: Vec<Arc<dyn Object>>,
     aabb: AxisAlignedBoundingBox,
+    /// Picks objects by power when this is sampled as a list of lights. Built on first use.
+    light_table: OnceLock<AliasTable>,
 }
 
 impl ObjectList {
     pub fn add(&mut self, o: impl Object + 'static) {
         self.aabb = self.aabb.merge(o.bounding_box());
-        self.objects.push(Box::new(o))
+        self.objects.push(Arc::new(o));
+        self.light_table = OnceLock::new();
     }
 
     pub fn add_all(&mut self, o: impl IntoIterator<Item = impl Object + 'static>) {
@@ -474,14 +662,27 @@ impl ObjectList {
         self.objects.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
     pub fn condense(&mut self) {
         let objects = take(&mut self.objects);
-        self.objects.push(Box::new(BvhNode::from(objects)))
+        self.objects.push(Arc::new(BvhNode::from(objects)));
+        self.light_table = OnceLock::new();
+    }
+
+    fn light_table(&self) -> &AliasTable {
+        self.light_table.get_or_init(|| {
+            let mut powers: Vec<f64> = self.objects.iter().map(|o| o.power()).collect();
+            sampling_weights(&mut powers);
+            AliasTable::new(&powers)
+        })
     }
 }
 
 impl Object for ObjectList {
-    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord> {
+    fn hit(&self, r: Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
         let mut closest = ray_t.max;
         let mut hit_record = None;
         for obj in &self.objects {
@@ -495,13 +696,33 @@ impl Object for ObjectList {
     fn bounding_box(&self) -> AxisAlignedBoundingBox {
         self.aabb
     }
-    // TODO this is bad
+    // Lights are picked in proportion to their power, or uniformly if none of them know it.
     fn pdf_value(&self, origin: Point, direction: Vec3) -> f64 {
-        let weight = (self.objects.len() as f64).recip();
-        self.objects.iter().map(|o| weight * o.pdf_value(origin, direction)).sum()
+        if self.objects.is_empty() {
+            return 0.0;
+        }
+        let table = self.light_table();
+        self.objects
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| table.pmf(i) > 0.0)
+            .map(|(i, o)| table.pmf(i) * o.pdf_value(origin, direction))
+            .sum()
     }
     fn random(&self, origin: Point) -> Vec3 {
-        use rand::seq::SliceRandom;
-        self.objects.choose(&mut thread_rng()).unwrap().random(origin)
+        self.objects[self.light_table().sample(random_double())].random(origin)
+    }
+    fn power(&self) -> f64 {
+        self.objects.iter().map(|o| o.power()).sum()
+    }
+    fn light_bounds(&self) -> LightBounds {
+        self.objects
+            .iter()
+            .map(|o| o.light_bounds())
+            .reduce(LightBounds::merge)
+            .unwrap_or(LightBounds::omnidirectional(self.aabb, 0.0))
+    }
+    fn children(&self) -> Vec<Arc<dyn Object>> {
+        self.objects.clone()
     }
 }
@@ -1,13 +1,31 @@
 use crate::material::AnyMaterial;
-use crate::vec3::Point;
+use crate::vec3::{Point, Vec3};
 
-use super::Triangle;
+use super::{ObjectList, Quad, Triangle};
 
 pub fn tetrahedron(a: Point, b: Point, c: Point, d: Point, m: AnyMaterial) -> [Triangle; 4] {
     [
-        Triangle::new(a, b, c, m),
-        Triangle::new(a, b, d, m),
-        Triangle::new(b, c, d, m),
+        Triangle::new(a, b, c, m.clone()),
+        Triangle::new(a, b, d, m.clone()),
+        Triangle::new(b, c, d, m.clone()),
         Triangle::new(a, c, d, m),
     ]
 }
+
+/// A triangular prism with base `a`, `b`, `c` extruded along `height`.
+///
+/// The base must wind counterclockwise when seen from the top (`(b - a).cross(c - a)` points
+/// along `height`) so that the faces point outward, which refraction depends on.
+pub fn prism(a: Point, b: Point, c: Point, height: Vec3, m: AnyMaterial) -> ObjectList {
+    let mut list = ObjectList::default();
+    list.add_all([
+        Triangle::new(a, c, b, m.clone()),
+        Triangle::new(a + height, b + height, c + height, m.clone()),
+    ]);
+    list.add_all([
+        Quad::new(a, b - a, height, m.clone()),
+        Quad::new(b, c - b, height, m.clone()),
+        Quad::new(c, a - c, height, m),
+    ]);
+    list
+}
@@ -7,17 +7,39 @@ pub struct Onb {
 impl Onb {
     pub fn new(n: Vec3) -> Self {
         let w = n.unit_vector();
-        let a = if n.0.abs() > 0.9 {
+        let a = if w.0.abs() > 0.9 {
             Vec3(0.0,1.0,0.0)
         } else {
             Vec3(1.0,0.0,0.0)
         };
-        let v = n.cross(a).unit_vector();
-        let u = n.cross(v);
+        let v = w.cross(a).unit_vector();
+        let u = w.cross(v);
 
         Self { axis: [u, v, w] }
     }
 
+    /// A basis around the normal `n` with `u` along `tangent`, e.g. the direction in which a
+    /// texture's u coordinate increases. Falls back to an arbitrary `u` if `tangent` is zero or
+    /// parallel to `n`.
+    pub fn from_normal_tangent(n: Vec3, tangent: Vec3) -> Self {
+        let w = n.unit_vector();
+        let u = tangent - tangent.dot(w) * w;
+        if u.near_zero() {
+            return Self::new(w);
+        }
+        let u = u.unit_vector();
+        let v = w.cross(u);
+        Self { axis: [u, v, w] }
+    }
+
+    pub fn u(&self) -> Vec3 {
+        self.axis[0]
+    }
+
+    pub fn v(&self) -> Vec3 {
+        self.axis[1]
+    }
+
     pub fn w(&self) -> Vec3 {
         self.axis[2]
     }
@@ -2,6 +2,7 @@ use std::f64::consts::{FRAC_1_PI, PI};
 
 use rand::random;
 
+use crate::environment::Environment;
 use crate::object::Object;
 use crate::onb::Onb;
 use crate::vec3::{Point, Vec3};
@@ -69,6 +70,17 @@ impl<T: Object> Pdf for ObjectPdf<T> {
     }
 }
 
+pub struct EnvironmentPdf<'a>(pub &'a dyn Environment);
+
+impl Pdf for EnvironmentPdf<'_> {
+    fn value(&self, direction: Vec3) -> f64 {
+        self.0.pdf(direction)
+    }
+    fn generate(&self) -> Vec3 {
+        self.0.sample()
+    }
+}
+
 pub struct MixturePdf<A, B>(A, B);
 
 impl<A: Pdf, B: Pdf> MixturePdf<A, B> {
@@ -0,0 +1,60 @@
+use crate::camera::{Aperture, CameraBuilder};
+use crate::color::Color;
+use crate::light::Lights;
+use crate::material::{DiffuseLight, Lambertian, Metal};
+use crate::object::{ObjectList, Quad, Sphere};
+use crate::utils::random_double_in;
+use crate::vec3::{Point, Vec3};
+
+use super::Scene;
+
+/// A sphere in focus in front of a scattering of small lights far behind it, which a wide
+/// hexagonal aperture blurs into hexagons that turn into cat's eyes towards the corners.
+pub fn bokeh() -> Scene {
+    let mut world = ObjectList::default();
+
+    world.add(Quad::new(
+        Point::new(-20.0, 0.0, -40.0),
+        Vec3::new(40.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 50.0),
+        Lambertian::new((0.3, 0.3, 0.3)),
+    ));
+    world.add(Sphere::new(
+        Point::new(0.0, 1.0, 0.0),
+        1.0,
+        Metal::new((0.9, 0.8, 0.6), 0.05),
+    ));
+    world.add(Sphere::new(
+        Point::new(0.0, 8.0, 6.0),
+        2.0,
+        DiffuseLight::new(Color::new(3.0, 3.0, 3.0)),
+    ));
+
+    for _ in 0..60 {
+        let center = Point::new(
+            random_double_in(-16.0, 16.0),
+            random_double_in(0.5, 10.0),
+            random_double_in(-35.0, -25.0),
+        );
+        let color = Color::new(1.0, random_double_in(0.4, 0.8), random_double_in(0.1, 0.4));
+        world.add(Sphere::new(center, 0.15, DiffuseLight::new(color * 30.0)));
+    }
+
+    let camera = CameraBuilder::new()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(600)
+        .samples_per_pixel(400)
+        .max_depth(50)
+        .vfov(30.0)
+        .look_from(Point::new(0.0, 1.5, 6.0))
+        .look_at(Point::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(5.0)
+        .aperture(Aperture::Polygon { blades: 6, rotation: 90.0 })
+        .cat_eye(0.8)
+        .background(Color::new(0.0, 0.0, 0.0))
+        .autofocus(&world)
+        .build();
+
+    Scene { camera, world, lights: Lights::default() }
+}
@@ -1,5 +1,6 @@
 use crate::camera::CameraBuilder;
 use crate::color::Color;
+use crate::light::Lights;
 use crate::material::{DiffuseLight, Lambertian};
 use crate::object::{box_3d, ObjectList, Quad, RotateY, Translate};
 use crate::vec3::{Point, Vec3};
@@ -12,7 +13,7 @@ pub fn cornell_box() -> Scene {
     let red = Lambertian::new((0.65, 0.05, 0.05));
     let white = Lambertian::new((0.73, 0.73, 0.73));
     let green = Lambertian::new((0.12, 0.45, 0.15));
-    let light = DiffuseLight(Color::new(15.0, 15.0, 15.0));
+    let light = DiffuseLight::new(Color::new(15.0, 15.0, 15.0));
 
     world.add(Quad::new(
         Point::new(555.0, 0.0, 0.0),
@@ -84,5 +85,5 @@ pub fn cornell_box() -> Scene {
         .defocus_angle(0.0)
         .build();
 
-    Scene { camera, world, light: Box::new(light) }
+    Scene { camera, world, lights: Lights::default() }
 }
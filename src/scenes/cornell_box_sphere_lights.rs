@@ -0,0 +1,70 @@
+use crate::camera::CameraBuilder;
+use crate::color::Color;
+use crate::light::Lights;
+use crate::material::{DiffuseLight, Lambertian};
+use crate::object::{box_3d, Quad, RotateY, Sphere, Translate};
+use crate::vec3::{Point, Vec3};
+
+use super::{cornell_walls, Scene};
+
+/// The Cornell box with a small, bright sphere light over the short box and a row of dim ones
+/// along the back wall, which is noisy unless lights are picked by their power.
+pub fn cornell_box_sphere_lights() -> Scene {
+    let mut world = cornell_walls();
+
+    let white = Lambertian::new((0.73, 0.73, 0.73));
+    let light = DiffuseLight::new(Color::new(15.0, 15.0, 15.0));
+
+    let box1 = box_3d(
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(165.0, 330.0, 165.0),
+        white,
+    );
+    let box1 = RotateY::new(box1, 15.0);
+    let box1 = Translate::new(box1, Vec3(265.0, 0.0, 295.0));
+    world.add(box1);
+
+    let box2 = box_3d(
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(165.0, 165.0, 165.0),
+        white,
+    );
+    let box2 = RotateY::new(box2, -18.0);
+    let box2 = Translate::new(box2, Vec3(130.0, 0.0, 65.0));
+    world.add(box2);
+
+    world.add(Quad::new(
+        Point::new(343.0, 554.0, 332.0),
+        Vec3::new(-130.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -105.0),
+        light,
+    ));
+
+    world.add(Sphere::new(
+        Point::new(212.0, 260.0, 147.0),
+        10.0,
+        DiffuseLight::new(Color::new(200.0, 180.0, 140.0)),
+    ));
+    for i in 0..8 {
+        world.add(Sphere::new(
+            Point::new(60.0 + 62.0 * i as f64, 480.0, 530.0),
+            8.0,
+            DiffuseLight::new(Color::new(0.5, 1.0, 2.0)),
+        ));
+    }
+
+    let camera = CameraBuilder::new()
+        .aspect_ratio(1.0)
+        .image_width(600)
+        .samples_per_pixel(1024)
+        .max_depth(50)
+        .background(Color::new(0.0, 0.0, 0.0))
+        .vfov(40.0)
+        .look_from(Point::new(278.0, 278.0, -800.0))
+        .look_at(Point::new(278.0, 278.0, 0.0))
+        .vup(Vec3(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .build();
+
+    Scene { camera, world, lights: Lights::default() }
+}
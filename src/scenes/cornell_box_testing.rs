@@ -1,5 +1,6 @@
 use crate::camera::CameraBuilder;
 use crate::color::Color;
+use crate::light::Lights;
 use crate::material::{DiffuseLight, Lambertian, Metal};
 use crate::object::{box_3d, ObjectList, Quad, RotateY, Sphere, Translate};
 use crate::vec3::{Point, Vec3};
@@ -12,7 +13,7 @@ pub fn cornell_box_testing() -> Scene {
     let red = Lambertian::new((0.65, 0.05, 0.05));
     let white = Lambertian::new((0.73, 0.73, 0.73));
     let green = Lambertian::new((0.12, 0.45, 0.15));
-    let light = DiffuseLight(Color::new(15.0, 15.0, 15.0));
+    let light = DiffuseLight::new(Color::new(15.0, 15.0, 15.0));
 
     world.add(Quad::new(
         Point::new(555.0, 0.0, 0.0),
@@ -74,9 +75,9 @@ pub fn cornell_box_testing() -> Scene {
         light,
     );
 
-    let mut lights = ObjectList::default();
-    lights.add(light);
-    // lights.add(sphere);
+    let mut lights = Lights::default();
+    lights.add_area(light.clone());
+    // lights.add_area(sphere);
 
     world.add(light);
 
@@ -93,5 +94,5 @@ pub fn cornell_box_testing() -> Scene {
         .defocus_angle(0.0)
         .build();
 
-    Scene { camera, world, light: Box::new(light) }
+    Scene { camera, world, lights }
 }
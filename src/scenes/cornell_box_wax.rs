@@ -0,0 +1,53 @@
+use crate::camera::CameraBuilder;
+use crate::color::Color;
+use crate::light::Lights;
+use crate::material::{DiffuseLight, Subsurface};
+use crate::object::{box_3d, Quad, RotateY, Sphere, Translate};
+use crate::vec3::{Point, Vec3};
+
+use super::{cornell_walls, Scene};
+
+/// The Cornell box with a wax sphere and a thin wax slab, to show light scattering through them.
+pub fn cornell_box_wax() -> Scene {
+    let mut world = cornell_walls();
+
+    let light = DiffuseLight::new(Color::new(15.0, 15.0, 15.0));
+
+    // a warm candle wax, red light travels furthest through it.
+    let wax = Subsurface::from_albedo(1.45, (0.99, 0.97, 0.93), 15.0);
+
+    let sphere = Sphere::new(Point::new(190.0, 90.0, 190.0), 90.0, wax);
+    world.add(sphere);
+
+    let slab = box_3d(
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(165.0, 300.0, 20.0),
+        wax,
+    );
+    let slab = RotateY::new(slab, -20.0);
+    let slab = Translate::new(slab, Vec3(330.0, 0.0, 330.0));
+    world.add(slab);
+
+    let light = Quad::new(
+        Point::new(343.0, 554.0, 332.0),
+        Vec3::new(-130.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -105.0),
+        light,
+    );
+    world.add(light);
+
+    let camera = CameraBuilder::new()
+        .aspect_ratio(1.0)
+        .image_width(600)
+        .samples_per_pixel(1024)
+        .max_depth(200)
+        .background(Color::new(0.0, 0.0, 0.0))
+        .vfov(40.0)
+        .look_from(Point::new(278.0, 278.0, -800.0))
+        .look_at(Point::new(278.0, 278.0, 0.0))
+        .vup(Vec3(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .build();
+
+    Scene { camera, world, lights: Lights::default() }
+}
@@ -0,0 +1,69 @@
+use crate::camera::CameraBuilder;
+use crate::color::Color;
+use crate::light::{DirectionalLight, Lights, PointLight, SpotLight};
+use crate::material::{Lambertian, OrenNayar};
+use crate::object::{ObjectList, Quad, Sphere};
+use crate::spectrum::blackbody_color;
+use crate::vec3::{Point, Vec3};
+
+use super::Scene;
+
+/// A few diffuse spheres lit only by lights without a surface: a spot light, a warm point light
+/// and a dim moon. Shiny materials would show nothing of them, see [`Light`](crate::light::Light).
+pub fn delta_lights() -> Scene {
+    let mut world = ObjectList::default();
+
+    world.add(Quad::new(
+        Point::new(-20.0, 0.0, -20.0),
+        Vec3::new(40.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 40.0),
+        Lambertian::new((0.5, 0.5, 0.5)),
+    ));
+    world.add(Sphere::new(
+        Point::new(-2.2, 1.0, 0.0),
+        1.0,
+        OrenNayar::new((0.8, 0.3, 0.2), 20.0),
+    ));
+    world.add(Sphere::new(
+        Point::new(0.0, 1.0, 0.0),
+        1.0,
+        Lambertian::new((0.8, 0.8, 0.8)),
+    ));
+    world.add(Sphere::new(
+        Point::new(2.2, 1.0, 0.0),
+        1.0,
+        Lambertian::new((0.2, 0.4, 0.8)),
+    ));
+
+    let mut lights = Lights::default();
+    lights.add(SpotLight::new(
+        Point::new(-2.0, 6.0, 3.0),
+        Point::new(-2.2, 0.0, 0.0),
+        Color::splat(60.0),
+        25.0,
+        15.0,
+    ));
+    lights.add(PointLight::new(
+        Point::new(3.0, 3.0, 2.0),
+        blackbody_color(2700.0) * 15.0,
+    ));
+    lights.add(DirectionalLight::new(
+        Vec3::new(1.0, -1.0, -0.5),
+        blackbody_color(8000.0) * 0.1,
+    ));
+
+    let camera = CameraBuilder::new()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(600)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(35.0)
+        .look_from(Point::new(0.0, 3.0, 10.0))
+        .look_at(Point::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .background(Color::new(0.0, 0.0, 0.0))
+        .build();
+
+    Scene { camera, world, lights }
+}
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use crate::camera::CameraBuilder;
+use crate::color::Color;
+use crate::light::Lights;
+use crate::material::{DiffuseLight, Lambertian, Metal};
+use crate::object::{AlphaMask, ObjectList, Quad, Sphere};
+use crate::texture::ProceduralMask;
+use crate::vec3::{Point, Vec3};
+
+use super::Scene;
+
+/// A chain-link fence cut out of a single quad with a procedural alpha mask, casting its shadow
+/// from an area light onto the ground and a sphere behind it.
+pub fn fence() -> Scene {
+    let mut world = ObjectList::default();
+
+    world.add(Quad::new(
+        Point::new(-10.0, 0.0, -10.0),
+        Vec3::new(20.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 20.0),
+        Lambertian::new((0.6, 0.6, 0.55)),
+    ));
+    world.add(Sphere::new(
+        Point::new(0.0, 1.0, -2.0),
+        1.0,
+        Lambertian::new((0.7, 0.2, 0.1)),
+    ));
+
+    let lattice = Arc::new(ProceduralMask(|u: f64, v: f64, _| {
+        let wire = |x: f64| (x * 12.0).rem_euclid(1.0) < 0.12;
+        if wire(u + v) || wire(u - v) { 1.0 } else { 0.0 }
+    }));
+    let fence = Quad::new(
+        Point::new(-3.0, 0.0, 0.0),
+        Vec3::new(6.0, 0.0, 0.0),
+        Vec3::new(0.0, 3.0, 0.0),
+        Metal::new((0.6, 0.6, 0.6), 0.3),
+    );
+    world.add(AlphaMask::new(fence, lattice, 0.5));
+
+    let light = Quad::new(
+        Point::new(-1.0, 6.0, 2.0),
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 2.0),
+        DiffuseLight::new(Color::new(20.0, 20.0, 20.0)),
+    );
+    world.add(light);
+
+    let camera = CameraBuilder::new()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(600)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .vfov(40.0)
+        .look_from(Point::new(2.0, 2.5, 7.0))
+        .look_at(Point::new(0.0, 1.0, -1.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .background(Color::new(0.05, 0.05, 0.08))
+        .build();
+
+    Scene { camera, world, lights: Lights::default() }
+}
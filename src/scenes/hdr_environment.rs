@@ -0,0 +1,30 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::camera::CameraBuilder;
+use crate::environment::EnvironmentMap;
+use crate::light::Lights;
+use crate::vec3::{Point, Vec3};
+
+use super::{three_spheres, Scene};
+
+/// Three spheres on a ground sphere, lit only by the environment map at `path` (a Radiance
+/// `.hdr` or a `.pfm`).
+pub fn hdr_environment(path: impl AsRef<Path>) -> color_eyre::Result<Scene> {
+    let environment = Arc::new(EnvironmentMap::load(path)?);
+
+    let camera = CameraBuilder::new()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(600)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(35.0)
+        .look_from(Point::new(0.0, 2.0, 10.0))
+        .look_at(Point::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .environment(environment)
+        .build();
+
+    Ok(Scene { camera, world: three_spheres(), lights: Lights::default() })
+}
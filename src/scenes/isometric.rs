@@ -0,0 +1,51 @@
+use crate::camera::{CameraBuilder, Filter};
+use crate::light::Lights;
+use crate::material::Lambertian;
+use crate::object::{box_3d, ObjectList, Quad};
+use crate::utils::random_double_in;
+use crate::vec3::{Point, Vec3};
+
+use super::{clear_sky, Scene};
+
+/// A block of buildings seen through an orthographic camera from the classic isometric angle,
+/// so that parallel edges stay parallel in the image. A Mitchell filter keeps the many straight
+/// edges crisp without jaggies.
+pub fn isometric() -> Scene {
+    let mut world = ObjectList::default();
+
+    world.add(Quad::new(
+        Point::new(-10.0, 0.0, -10.0),
+        Vec3::new(20.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 20.0),
+        Lambertian::new((0.4, 0.45, 0.4)),
+    ));
+    for i in -3..3 {
+        for j in -3..3 {
+            let corner = Point::new(i as f64 * 1.5 + 0.2, 0.0, j as f64 * 1.5 + 0.2);
+            let height = random_double_in(0.5, 3.0);
+            let shade = random_double_in(0.5, 0.8);
+            world.add(box_3d(
+                corner,
+                corner + Vec3::new(1.1, height, 1.1),
+                Lambertian::new((shade, shade * 0.9, shade * 0.8)),
+            ));
+        }
+    }
+
+    // equal angles to all three axes
+    let camera = CameraBuilder::new()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(600)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .filter(Filter::Mitchell { radius: 2.0, b: 1.0 / 3.0, c: 1.0 / 3.0 })
+        .orthographic(10.0)
+        .look_from(Point::new(20.0, 20.0, 20.0))
+        .look_at(Point::new(0.0, 0.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .environment(clear_sky(Vec3::new(-0.6, 1.0, 0.3)))
+        .build();
+
+    Scene { camera, world, lights: Lights::default() }
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use crate::camera::CameraBuilder;
+use crate::color::Color;
+use crate::light::Lights;
+use crate::material::{DiffuseLight, Lambertian, Metal};
+use crate::object::{ObjectList, Quad, Sphere};
+use crate::texture::CheckerTexture;
+use crate::vec3::{Point, Vec3};
+
+use super::Scene;
+
+/// Lights set up the way a lighting designer would: a warm 2700 K panel standing between two
+/// spheres that it lights from both sides, a cool 6500 K bulb, both given in watts, and a
+/// checkered screen behind them.
+pub fn lamps() -> Scene {
+    let mut world = ObjectList::default();
+
+    world.add(Quad::new(
+        Point::new(-10.0, 0.0, -10.0),
+        Vec3::new(20.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 20.0),
+        Lambertian::new((0.5, 0.5, 0.5)),
+    ));
+    world.add(Sphere::new(
+        Point::new(-1.5, 0.8, 0.0),
+        0.8,
+        Lambertian::new((0.8, 0.8, 0.8)),
+    ));
+    world.add(Sphere::new(
+        Point::new(1.5, 0.8, 0.0),
+        0.8,
+        Metal::new((0.8, 0.8, 0.8), 0.2),
+    ));
+
+    let (width, height) = (1.2, 1.6);
+    world.add(Quad::new(
+        Point::new(0.0, 0.1, -0.6),
+        Vec3::new(0.0, 0.0, width),
+        Vec3::new(0.0, height, 0.0),
+        DiffuseLight::from_temperature(2700.0)
+            .two_sided()
+            .with_power(70.0),
+    ));
+
+    let radius = 0.3;
+    world.add(Sphere::new(
+        Point::new(2.5, 3.0, 1.0),
+        radius,
+        DiffuseLight::from_temperature(6500.0).with_power(30.0),
+    ));
+
+    let screen = CheckerTexture::new(0.25, (1.0, 0.4, 0.1), (0.1, 0.3, 1.0));
+    world.add(Quad::new(
+        Point::new(-2.0, 0.5, -3.0),
+        Vec3::new(4.0, 0.0, 0.0),
+        Vec3::new(0.0, 2.25, 0.0),
+        DiffuseLight::textured(Arc::new(screen)).with_intensity(0.8),
+    ));
+
+    let camera = CameraBuilder::new()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(35.0)
+        .look_from(Point::new(0.0, 2.5, 9.0))
+        .look_at(Point::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .background(Color::new(0.0, 0.0, 0.0))
+        .build();
+
+    Scene { camera, world, lights: Lights::default() }
+}
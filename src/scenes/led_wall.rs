@@ -0,0 +1,87 @@
+use crate::camera::CameraBuilder;
+use crate::color::Color;
+use crate::light::Lights;
+use crate::material::{DiffuseLight, Lambertian, Metal};
+use crate::object::{ObjectList, Quad, Sphere, Triangle};
+use crate::vec3::{Point, Vec3};
+
+use super::Scene;
+
+const COLUMNS: usize = 48;
+const ROWS: usize = 24;
+
+/// A few spheres in front of a wall of over a thousand small colored emitters, with a sign of
+/// triangles on its back that can't light anything in front of it.
+pub fn led_wall() -> Scene {
+    let mut world = ObjectList::default();
+
+    world.add(Quad::new(
+        Point::new(-20.0, 0.0, -20.0),
+        Vec3::new(40.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 40.0),
+        Lambertian::new((0.5, 0.5, 0.5)),
+    ));
+    world.add(Sphere::new(
+        Point::new(-2.2, 1.0, 0.0),
+        1.0,
+        Lambertian::new((0.8, 0.8, 0.8)),
+    ));
+    world.add(Sphere::new(
+        Point::new(0.0, 1.0, 0.0),
+        1.0,
+        Metal::new((0.8, 0.8, 0.8), 0.1),
+    ));
+    world.add(Sphere::new(
+        Point::new(2.2, 1.0, 0.0),
+        1.0,
+        Lambertian::new((0.8, 0.8, 0.8)),
+    ));
+
+    // the wall, with a dark panel behind the LEDs
+    let (left, bottom, z) = (-8.0, 0.5, -4.0);
+    let pitch = 16.0 / COLUMNS as f64;
+    world.add(Quad::new(
+        Point::new(left, bottom, z - 0.01),
+        Vec3::new(16.0, 0.0, 0.0),
+        Vec3::new(0.0, pitch * ROWS as f64, 0.0),
+        Lambertian::new((0.05, 0.05, 0.05)),
+    ));
+    for i in 0..COLUMNS {
+        for j in 0..ROWS {
+            let (s, t) = (i as f64 / COLUMNS as f64, j as f64 / ROWS as f64);
+            let color = Color::new(s, 0.3 * (1.0 - t), 1.0 - s) * 4.0;
+            world.add(Quad::new(
+                Point::new(left + pitch * i as f64, bottom + pitch * j as f64, z),
+                Vec3::new(0.6 * pitch, 0.0, 0.0),
+                Vec3::new(0.0, 0.6 * pitch, 0.0),
+                DiffuseLight::new(color),
+            ));
+        }
+    }
+
+    // a bright sign on the back, facing away
+    for k in 0..64 {
+        let x = left + 0.25 * k as f64;
+        world.add(Triangle::new(
+            Point::new(x, 3.0, z - 0.02),
+            Point::new(x + 0.2, 4.0, z - 0.02),
+            Point::new(x + 0.2, 3.0, z - 0.02),
+            DiffuseLight::new(Color::new(20.0, 20.0, 20.0)),
+        ));
+    }
+
+    let camera = CameraBuilder::new()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(600)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(40.0)
+        .look_from(Point::new(0.0, 3.0, 10.0))
+        .look_at(Point::new(0.0, 2.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .background(Color::new(0.0, 0.0, 0.0))
+        .build();
+
+    Scene { camera, world, lights: Lights::default() }
+}
@@ -13,15 +13,60 @@ pub use cornell_box::cornell_box;
 mod cornell_box_testing;
 pub use cornell_box_testing::cornell_box_testing;
 
+mod cornell_box_sphere_lights;
+pub use cornell_box_sphere_lights::cornell_box_sphere_lights;
+mod cornell_box_wax;
+pub use cornell_box_wax::cornell_box_wax;
+mod lamps;
+pub use lamps::lamps;
+
+mod soap_bubble;
+pub use soap_bubble::soap_bubble;
+
+mod prism;
+pub use prism::prism_dispersion;
+
+mod fence;
+pub use fence::fence;
+mod normal_maps;
+pub use normal_maps::normal_maps;
+
+mod delta_lights;
+pub use delta_lights::delta_lights;
+mod led_wall;
+pub use led_wall::led_wall;
+
+mod hdr_environment;
+pub use hdr_environment::hdr_environment;
+mod sky;
+pub use sky::sky;
+mod isometric;
+pub use isometric::isometric;
+mod panorama;
+pub use panorama::{panorama, vr};
+mod bokeh;
+pub use bokeh::bokeh;
+mod portrait;
+pub use portrait::portrait;
+mod turntable;
+pub use turntable::turntable;
+mod tilt_shift;
+pub use tilt_shift::{architecture, miniature};
+
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use crate::camera::Camera;
-use crate::object::{Object, ObjectList};
+use crate::camera::{Camera, CameraBuilder, CameraPath};
+use crate::environment::Sky;
+use crate::light::Lights;
+use crate::material::{Dielectric, Lambertian, Metal};
+use crate::object::{ObjectList, Quad, Sphere};
+use crate::vec3::{Point, Vec3};
 
 pub struct Scene {
     camera: Camera,
     world: ObjectList,
-    light: Box<dyn Object>,
+    lights: Lights,
 }
 
 impl Scene {
@@ -38,11 +83,128 @@ impl Scene {
     }
 
     pub fn render(mut self) -> color_eyre::Result<()> {
-        if self.world.len() > 10 {
-            self.world.condense();
+        prepare(&mut self.world, &mut self.lights);
+        self.camera.render(&self.world, &self.lights, "image.png")
+    }
+}
+
+/// A scene rendered once per frame as the camera moves along a path, to `frame_0001.png`,
+/// `frame_0002.png` and so on. The world is only prepared once for all of them.
+pub struct Sequence {
+    /// Everything but what the keyframes of `path` set.
+    camera: CameraBuilder,
+    path: CameraPath,
+    /// Frames per second.
+    frame_rate: f64,
+    world: ObjectList,
+    lights: Lights,
+}
+
+impl Sequence {
+    pub fn num_frames(&self) -> u64 {
+        (self.path.duration() * self.frame_rate) as u64 + 1
+    }
+
+    pub fn render_with_metrics(self) -> color_eyre::Result<()> {
+        let time = Instant::now();
+        let frames = self.num_frames();
+        self.render()?;
+        let elapsed = time.elapsed();
+        eprintln!(
+            "Done! Elapsed: {elapsed:?}, {}",
+            time_per(elapsed / frames as u32, "frame")
+        );
+        Ok(())
+    }
+
+    pub fn render(mut self) -> color_eyre::Result<()> {
+        prepare(&mut self.world, &mut self.lights);
+        for frame in 0..self.num_frames() {
+            let keyframe = self.path.at(self.path.start() + frame as f64 / self.frame_rate);
+            let camera = self.camera.keyframe(keyframe).build();
+            camera.render(&self.world, &self.lights, format!("frame_{:04}.png", frame + 1))?;
         }
+        Ok(())
+    }
+}
+
+/// The five walls of the Cornell box, 555 units on a side with its open side facing -z.
+fn cornell_walls() -> ObjectList {
+    let mut world = ObjectList::default();
 
-        self.camera.render(self.world, &*self.light)
+    let red = Lambertian::new((0.65, 0.05, 0.05));
+    let white = Lambertian::new((0.73, 0.73, 0.73));
+    let green = Lambertian::new((0.12, 0.45, 0.15));
+
+    world.add(Quad::new(
+        Point::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        green,
+    ));
+    world.add(Quad::new(
+        Point::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        red,
+    ));
+    world.add(Quad::new(
+        Point::new(0.0, 0.0, 0.0),
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        white,
+    ));
+    world.add(Quad::new(
+        Point::new(555.0, 555.0, 555.0),
+        Vec3::new(-555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -555.0),
+        white,
+    ));
+    world.add(Quad::new(
+        Point::new(0.0, 0.0, 555.0),
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        white,
+    ));
+    world
+}
+
+/// A clear sky with the sun in `sun_direction`.
+fn clear_sky(sun_direction: Vec3) -> Arc<Sky> {
+    Arc::new(Sky::new(sun_direction, 3.0))
+}
+
+/// A diffuse, a glass and a metal sphere in a row on a ground sphere, for scenes that are about
+/// how things are lit.
+fn three_spheres() -> ObjectList {
+    let mut world = ObjectList::default();
+
+    world.add(Sphere::new(
+        Point::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Lambertian::new((0.5, 0.5, 0.5)),
+    ));
+    world.add(Sphere::new(
+        Point::new(-2.2, 1.0, 0.0),
+        1.0,
+        Lambertian::new((0.8, 0.3, 0.2)),
+    ));
+    world.add(Sphere::new(Point::new(0.0, 1.0, 0.0), 1.0, Dielectric::new(1.5)));
+    world.add(Sphere::new(
+        Point::new(2.2, 1.0, 0.0),
+        1.0,
+        Metal::new((0.8, 0.8, 0.8), 0.05),
+    ));
+    world
+}
+
+/// Finds the lights and builds the BVH.
+fn prepare(world: &mut ObjectList, lights: &mut Lights) {
+    if lights.area().is_none() {
+        lights.discover(world);
+    }
+    if world.len() > 10 {
+        world.condense();
     }
 }
 
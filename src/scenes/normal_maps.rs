@@ -0,0 +1,77 @@
+use std::f64::consts::{PI, TAU};
+use std::sync::Arc;
+
+use image::{Rgba, Rgba32FImage};
+
+use crate::camera::CameraBuilder;
+use crate::color::Color;
+use crate::light::Lights;
+use crate::material::{DiffuseLight, Lambertian, Metal, NormalMapped};
+use crate::object::{ObjectList, Quad, Sphere};
+use crate::texture::ImageTexture;
+use crate::vec3::{Point, Vec3};
+
+use super::Scene;
+
+/// A tiled floor made of a single quad with a normal map, a rippled bump mapped sphere and a
+/// metal sphere with the same tiles, under a light off to the side so the bumps cast highlights.
+pub fn normal_maps() -> Scene {
+    let mut world = ObjectList::default();
+
+    // 8 by 8 tiles with beveled edges
+    let tiles = Arc::new(ImageTexture::new(Rgba32FImage::from_fn(512, 512, |i, j| {
+        let bevel = |p: u32| match (p % 64) as f64 / 64.0 {
+            t if t < 0.08 => -0.6,
+            t if t > 0.92 => 0.6,
+            _ => 0.0,
+        };
+        // the image is upside down in v
+        let normal: Vec3 = Vec3(bevel(i), -bevel(j), 1.0).unit_vector();
+        let [x, y, z] = [normal.0, normal.1, normal.2].map(|c| (0.5 * c + 0.5) as f32);
+        Rgba([x, y, z, 1.0])
+    })));
+    world.add(Quad::new(
+        Point::new(-4.0, 0.0, -4.0),
+        Vec3::new(8.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 8.0),
+        NormalMapped::normal_map(Lambertian::new((0.6, 0.55, 0.5)), tiles.clone()),
+    ));
+
+    let ripples = Arc::new(ImageTexture::new(Rgba32FImage::from_fn(512, 256, |i, j| {
+        let (u, v) = (i as f64 / 512.0, j as f64 / 256.0);
+        let h = (0.5 + 0.5 * (u * TAU * 16.0).sin() * (v * PI * 8.0).sin()) as f32;
+        Rgba([h, h, h, 1.0])
+    })));
+    world.add(Sphere::new(
+        Point::new(-1.1, 1.0, 0.0),
+        1.0,
+        NormalMapped::bump(Lambertian::new((0.2, 0.4, 0.7)), ripples, 0.01),
+    ));
+    world.add(Sphere::new(
+        Point::new(1.1, 1.0, 0.0),
+        1.0,
+        NormalMapped::normal_map(Metal::new((0.8, 0.8, 0.8), 0.05), tiles),
+    ));
+
+    world.add(Quad::new(
+        Point::new(-5.0, 3.0, -1.0),
+        Vec3::new(0.0, 2.0, 0.0),
+        Vec3::new(0.0, 0.0, 2.0),
+        DiffuseLight::new(Color::new(15.0, 15.0, 15.0)),
+    ));
+
+    let camera = CameraBuilder::new()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(600)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .vfov(35.0)
+        .look_from(Point::new(0.0, 3.0, 7.0))
+        .look_at(Point::new(0.0, 0.8, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .background(Color::new(0.1, 0.1, 0.12))
+        .build();
+
+    Scene { camera, world, lights: Lights::default() }
+}
@@ -0,0 +1,76 @@
+use std::f64::consts::TAU;
+
+use crate::camera::{CameraBuilder, CameraModel, StereoLayout};
+use crate::light::Lights;
+use crate::material::{AnyMaterial, Dielectric, Lambertian, Metal};
+use crate::object::{ObjectList, Quad, Sphere};
+use crate::vec3::{Point, Vec3};
+
+use super::{clear_sky, Scene};
+
+/// A ring of spheres all around the camera under a clear sky, for panoramic camera models like
+/// [`CameraModel::Equirectangular`] and [`CameraModel::Fisheye`].
+pub fn panorama(model: CameraModel) -> Scene {
+    let aspect_ratio = match model {
+        CameraModel::Equirectangular => 2.0,
+        CameraModel::Fisheye { .. } => 1.0,
+        _ => 16.0 / 9.0,
+    };
+    let camera = CameraBuilder::new()
+        .aspect_ratio(aspect_ratio)
+        .image_width(600)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .model(model)
+        .vfov(60.0)
+        .look_from(Point::new(0.0, 1.5, 0.0))
+        .look_at(Point::new(0.0, 1.5, -1.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .environment(clear_sky(Vec3::new(-1.0, 0.8, -0.6)))
+        .build();
+
+    Scene { camera, world: world(), lights: Lights::default() }
+}
+
+/// The scene of [`panorama`] as a 360° stereo image for VR headsets, with the left eye on
+/// top.
+pub fn vr() -> Scene {
+    let camera = CameraBuilder::new()
+        .aspect_ratio(2.0)
+        .image_width(600)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .model(CameraModel::Equirectangular)
+        .stereo(0.064, 4.0, StereoLayout::TopBottom)
+        .look_from(Point::new(0.0, 1.5, 0.0))
+        .look_at(Point::new(0.0, 1.5, -1.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .environment(clear_sky(Vec3::new(-1.0, 0.8, -0.6)))
+        .build();
+
+    Scene { camera, world: world(), lights: Lights::default() }
+}
+
+fn world() -> ObjectList {
+    let mut world = ObjectList::default();
+
+    world.add(Quad::new(
+        Point::new(-50.0, 0.0, -50.0),
+        Vec3::new(100.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 100.0),
+        Lambertian::new((0.5, 0.5, 0.5)),
+    ));
+    for k in 0..8 {
+        let angle = k as f64 / 8.0 * TAU;
+        let center = Point::new(4.0 * angle.sin(), 1.0, -4.0 * angle.cos());
+        let material: AnyMaterial = match k % 3 {
+            0 => Lambertian::new((0.8, 0.3, 0.2)).into(),
+            1 => Metal::new((0.8, 0.8, 0.8), 0.05).into(),
+            _ => Dielectric::new(1.5).into(),
+        };
+        world.add(Sphere::new(center, 1.0, material));
+    }
+    world
+}
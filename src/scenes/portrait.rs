@@ -0,0 +1,53 @@
+use crate::camera::{CameraBuilder, PhysicalCamera};
+use crate::light::Lights;
+use crate::material::{Lambertian, Metal};
+use crate::object::{ObjectList, Sphere};
+use crate::vec3::{Point, Vec3};
+
+use super::{clear_sky, Scene};
+
+/// A row of spheres in the afternoon sun, taken with a portrait lens wide open so that only the
+/// nearest one is sharp.
+pub fn portrait() -> Scene {
+    let mut world = ObjectList::default();
+
+    world.add(Sphere::new(
+        Point::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Lambertian::new((0.5, 0.5, 0.5)),
+    ));
+    for k in 0..6 {
+        let center = Point::new(-0.4 + 0.5 * k as f64, 0.3, -1.5 * k as f64);
+        let shade = 0.2 + 0.12 * k as f64;
+        world.add(Sphere::new(center, 0.3, Lambertian::new((0.8, shade, 0.2))));
+    }
+    world.add(Sphere::new(
+        Point::new(-1.2, 0.3, -4.0),
+        0.3,
+        Metal::new((0.8, 0.8, 0.8), 0.05),
+    ));
+
+    let environment = clear_sky(Vec3::new(-1.0, 0.8, -0.6));
+
+    // about as bright as the sunny 16 rule at f/1.8
+    let camera = CameraBuilder::new()
+        .aspect_ratio(3.0 / 2.0)
+        .image_width(600)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .physical(PhysicalCamera {
+            focal_length: 85.0,
+            f_number: 1.8,
+            iso: 100.0,
+            shutter: 1.0 / 8000.0,
+            ..Default::default()
+        })
+        .look_from(Point::new(0.0, 0.6, 4.0))
+        .look_at(Point::new(0.0, 0.2, -3.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .focus_dist(4.0)
+        .environment(environment)
+        .build();
+
+    Scene { camera, world, lights: Lights::default() }
+}
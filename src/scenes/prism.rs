@@ -0,0 +1,63 @@
+use crate::camera::CameraBuilder;
+use crate::color::Color;
+use crate::light::Lights;
+use crate::material::{Dielectric, DiffuseLight, Lambertian};
+use crate::object::polyhedra::prism;
+use crate::object::{ObjectList, Quad};
+use crate::vec3::{Point, Vec3};
+
+use super::Scene;
+
+/// A flint glass prism in front of white stripes, rendered spectrally so that the edges of the
+/// stripes seen through the prism split into rainbows.
+pub fn prism_dispersion() -> Scene {
+    let mut world = ObjectList::default();
+
+    world.add(Quad::new(
+        Point::new(-20.0, 0.0, -20.0),
+        Vec3::new(40.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 40.0),
+        Lambertian::new((0.3, 0.3, 0.3)),
+    ));
+
+    // Schott SF11 dense flint glass
+    let flint = Dielectric::sellmeier(
+        [1.737_596_95, 0.313_747_346, 1.898_781_01],
+        [0.013_188_707, 0.062_306_814_2, 155.236_29],
+    );
+    world.add(prism(
+        Point::new(-1.0, 0.0, 0.577),
+        Point::new(1.0, 0.0, 0.577),
+        Point::new(0.0, 0.0, -1.155),
+        Vec3::new(0.0, 2.5, 0.0),
+        flint.into(),
+    ));
+
+    let white = DiffuseLight::new(Color::new(1.0, 1.0, 1.0));
+    // wide enough to still be seen through the prism, which bends rays by about 50 degrees.
+    for i in -20..=20 {
+        let stripe = Quad::new(
+            Point::new(i as f64, 0.0, -4.0),
+            Vec3::new(0.4, 0.0, 0.0),
+            Vec3::new(0.0, 4.0, 0.0),
+            white.clone(),
+        );
+        world.add(stripe);
+    }
+
+    let camera = CameraBuilder::new()
+        .aspect_ratio(3.0 / 2.0)
+        .image_width(600)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(35.0)
+        .look_from(Point::new(0.0, 1.5, 6.0))
+        .look_at(Point::new(0.0, 1.2, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .background(Color::new(0.0, 0.0, 0.0))
+        .spectral(true)
+        .build();
+
+    Scene { camera, world, lights: Lights::default() }
+}
@@ -1,7 +1,8 @@
 use crate::camera::CameraBuilder;
 use crate::color::Color;
+use crate::light::Lights;
 use crate::material::Lambertian;
-use crate::object::{DummyObject, ObjectList, Quad};
+use crate::object::{ObjectList, Quad};
 use crate::vec3::{Point, Vec3};
 
 use super::Scene;
@@ -59,5 +60,5 @@ pub fn quads() -> Scene {
         .background(Color::new(0.7, 0.8, 1.0))
         .build();
 
-    Scene { camera, world, light: Box::new(DummyObject) }
+    Scene { camera, world, lights: Lights::default() }
 }
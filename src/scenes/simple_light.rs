@@ -1,5 +1,6 @@
 use crate::camera::CameraBuilder;
 use crate::color::Color;
+use crate::light::Lights;
 use crate::material::{DiffuseLight, Lambertian};
 use crate::object::{ObjectList, Quad, Sphere};
 use crate::vec3::{Point, Vec3};
@@ -21,7 +22,7 @@ pub fn simple_light() -> Scene {
         Lambertian::new(Color::new(0.9, 0.9, 0.9)),
     ));
 
-    let difflight = DiffuseLight(Color::new(4.0, 4.0, 4.0));
+    let difflight = DiffuseLight::new(Color::new(4.0, 4.0, 4.0));
 
     let light = Quad::new(
         Point::new(3.0, 1.0, -2.0),
@@ -44,5 +45,5 @@ pub fn simple_light() -> Scene {
         .defocus_angle(0.0)
         .build();
 
-    Scene { camera, world, light: Box::new(light) }
+    Scene { camera, world, lights: Lights::default() }
 }
@@ -0,0 +1,25 @@
+use crate::camera::CameraBuilder;
+use crate::light::Lights;
+use crate::vec3::{Point, Vec3};
+
+use super::{clear_sky, three_spheres, Scene};
+
+/// Three spheres on a ground sphere in the afternoon, lit by a clear sky and the sun.
+pub fn sky() -> Scene {
+    let environment = clear_sky(Vec3::new(-1.0, 0.8, -0.6));
+
+    let camera = CameraBuilder::new()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(600)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(35.0)
+        .look_from(Point::new(0.0, 2.0, 10.0))
+        .look_at(Point::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .environment(environment)
+        .build();
+
+    Scene { camera, world: three_spheres(), lights: Lights::default() }
+}
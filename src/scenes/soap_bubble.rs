@@ -0,0 +1,43 @@
+use crate::camera::CameraBuilder;
+use crate::color::Color;
+use crate::light::Lights;
+use crate::material::{Dielectric, Lambertian, Metal, ThinFilm};
+use crate::object::{ObjectList, Sphere};
+use crate::vec3::{Point, Vec3};
+
+use super::Scene;
+
+pub fn soap_bubble() -> Scene {
+    let mut world = ObjectList::default();
+
+    world.add(Sphere::new(
+        Point::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Lambertian::new((0.2, 0.3, 0.1)),
+    ));
+
+    // soap films are a few hundred nanometers thick, thinner films show fewer bands.
+    let bubbles = [(-2.2, 250.0), (0.0, 400.0), (2.2, 700.0)];
+    for (x, thickness) in bubbles {
+        let soap = Dielectric::new(1.0).with_thin_film(ThinFilm::new(thickness, 1.33));
+        world.add(Sphere::new(Point::new(x, 1.2, 0.0), 1.0, soap));
+    }
+
+    let oil_slick = Metal::new((0.6, 0.6, 0.6), 0.0).with_thin_film(ThinFilm::new(350.0, 1.5));
+    world.add(Sphere::new(Point::new(0.0, 0.6, -3.0), 0.6, oil_slick));
+
+    let camera = CameraBuilder::new()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(800)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .vfov(30.0)
+        .look_from(Point::new(0.0, 2.0, 10.0))
+        .look_at(Point::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .background(Color::new(0.7, 0.8, 1.0))
+        .build();
+
+    Scene { camera, world, lights: Lights::default() }
+}
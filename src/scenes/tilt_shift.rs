@@ -0,0 +1,74 @@
+use crate::camera::CameraBuilder;
+use crate::light::Lights;
+use crate::material::Lambertian;
+use crate::object::{box_3d, ObjectList, Quad};
+use crate::utils::random_double_in;
+use crate::vec3::{Point, Vec3};
+
+use super::{clear_sky, Scene};
+
+/// Tall buildings seen from the street by a level camera with its lens shifted up, so that their
+/// walls stay parallel instead of leaning in towards the top.
+pub fn architecture() -> Scene {
+    let camera = CameraBuilder::new()
+        .aspect_ratio(2.0 / 3.0)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(40.0)
+        .look_from(Point::new(2.0, 1.7, 38.0))
+        .look_at(Point::new(2.0, 1.7, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .shift_y(0.3)
+        .environment(clear_sky(Vec3::new(-0.6, 1.0, 0.3)))
+        .build();
+
+    Scene { camera, world: city(6.0, 14.0), lights: Lights::default() }
+}
+
+/// A city block from above with the focus plane tilted against the ground, leaving only a
+/// narrow band in focus so that it looks like a model.
+pub fn miniature() -> Scene {
+    let camera = CameraBuilder::new()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(600)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(40.0)
+        .look_from(Point::new(4.0, 14.0, 20.0))
+        .look_at(Point::new(0.0, 0.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .focus_dist(24.5)
+        .defocus_angle(4.0)
+        .tilt(-40.0)
+        .environment(clear_sky(Vec3::new(-0.6, 1.0, 0.3)))
+        .build();
+
+    Scene { camera, world: city(0.5, 3.0), lights: Lights::default() }
+}
+
+/// Blocks between streets, between `min_height` and `max_height` high.
+fn city(min_height: f64, max_height: f64) -> ObjectList {
+    let mut world = ObjectList::default();
+
+    world.add(Quad::new(
+        Point::new(-40.0, 0.0, -40.0),
+        Vec3::new(80.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 80.0),
+        Lambertian::new((0.35, 0.35, 0.35)),
+    ));
+    for i in -5..5 {
+        for j in -5..4 {
+            let corner = Point::new(i as f64 * 4.0 + 0.6, 0.0, j as f64 * 4.0 + 0.6);
+            let height = random_double_in(min_height, max_height);
+            let shade = random_double_in(0.5, 0.8);
+            world.add(box_3d(
+                corner,
+                corner + Vec3::new(2.8, height, 2.8),
+                Lambertian::new((shade, shade * 0.9, shade * 0.8)),
+            ));
+        }
+    }
+    world
+}
@@ -0,0 +1,41 @@
+use crate::camera::{CameraBuilder, CameraPath, Keyframe};
+use crate::light::Lights;
+use crate::vec3::{Point, Vec3};
+
+use super::{clear_sky, three_spheres, Sequence};
+
+/// The [`three_spheres`] under the sky of [`sky`](super::sky()), seen from a camera circling
+/// them once in four seconds, zooming in halfway through.
+pub fn turntable() -> Sequence {
+    let environment = clear_sky(Vec3::new(-1.0, 0.8, -0.6));
+
+    let mut path = CameraPath::default();
+    for k in 0..=4 {
+        let angle = (90.0 * k as f64).to_radians();
+        path.add(Keyframe {
+            time: k as f64,
+            look_from: Point::new(10.0 * angle.sin(), 2.0, 10.0 * angle.cos()),
+            look_at: Point::new(0.0, 1.0, 0.0),
+            vfov: if k == 2 { 20.0 } else { 35.0 },
+            focus_dist: 10.0,
+        });
+    }
+
+    let mut camera = CameraBuilder::new();
+    camera
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(400)
+        .samples_per_pixel(36)
+        .max_depth(50)
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .environment(environment);
+
+    Sequence {
+        camera,
+        path,
+        frame_rate: 24.0,
+        world: three_spheres(),
+        lights: Lights::default(),
+    }
+}
@@ -0,0 +1,299 @@
+use std::array;
+use std::ops::{Add, AddAssign, Mul};
+use std::sync::LazyLock;
+
+use crate::color::Color;
+use crate::material::{AnyMaterial, Material, ScatterRecord};
+use crate::object::HitRecord;
+use crate::ray::Ray;
+use crate::utils::random_double;
+use crate::vec3::Vec3;
+
+/// Shortest wavelength (in nm) that is rendered.
+pub const LAMBDA_MIN: f64 = 380.0;
+/// Longest wavelength (in nm) that is rendered.
+pub const LAMBDA_MAX: f64 = 780.0;
+/// Number of wavelengths carried along each path.
+pub const N_WAVELENGTHS: usize = 4;
+
+/// Light carried along a path, either as RGB or as a [`SampledSpectrum`].
+pub trait Radiance:
+    Copy + Default + Add<Output = Self> + Mul<Output = Self> + Mul<f64, Output = Self>
+{
+    fn is_finite(self) -> bool;
+}
+
+impl Radiance for Color {
+    fn is_finite(self) -> bool {
+        self.into_iter().all(f64::is_finite)
+    }
+}
+
+impl Radiance for SampledSpectrum {
+    fn is_finite(self) -> bool {
+        self.0.iter().all(|v| v.is_finite())
+    }
+}
+
+/// The wavelengths that a path carries light at, which the integrator is generic over: the
+/// three channels of [`Rgb`] or a few [`SampledWavelengths`].
+pub trait Wavelengths {
+    type Radiance: Radiance;
+    /// The radiance of an RGB color, such as what a material emits or its attenuation.
+    fn upsample(&self, color: Color) -> Self::Radiance;
+    /// Scatters a ray carrying these wavelengths off `material`.
+    fn scatter(
+        &mut self,
+        material: &AnyMaterial,
+        r_in: &Ray,
+        rec: &HitRecord,
+    ) -> Option<ScatterRecord>;
+}
+
+/// Plain RGB rendering, where dispersive materials use a single index of refraction.
+pub struct Rgb;
+
+impl Wavelengths for Rgb {
+    type Radiance = Color;
+    fn upsample(&self, color: Color) -> Color {
+        color
+    }
+    fn scatter(
+        &mut self,
+        material: &AnyMaterial,
+        r_in: &Ray,
+        rec: &HitRecord,
+    ) -> Option<ScatterRecord> {
+        material.scatter(r_in, rec)
+    }
+}
+
+/// The wavelengths carried by a path, sampled with hero wavelength sampling: the first one is
+/// uniformly random and the rest are spread evenly across the visible range from it.
+#[derive(Clone, Copy)]
+pub struct SampledWavelengths {
+    lambda: [f64; N_WAVELENGTHS],
+    pdf: [f64; N_WAVELENGTHS],
+}
+
+impl SampledWavelengths {
+    pub fn sample() -> Self {
+        let range = LAMBDA_MAX - LAMBDA_MIN;
+        let hero = random_double() * range;
+        let lambda = array::from_fn(|i| {
+            let offset = i as f64 * range / N_WAVELENGTHS as f64;
+            LAMBDA_MIN + (hero + offset) % range
+        });
+        Self {
+            lambda,
+            pdf: [range.recip(); N_WAVELENGTHS],
+        }
+    }
+
+    pub fn hero(&self) -> f64 {
+        self.lambda[0]
+    }
+
+    /// Drops every wavelength but the hero, for when a path takes a direction that depends on
+    /// the wavelength (e.g. refraction through a dispersive prism).
+    pub fn terminate_secondary(&mut self) {
+        if self.pdf[1..].iter().all(|&pdf| pdf == 0.0) {
+            return;
+        }
+        self.pdf[1..].fill(0.0);
+        self.pdf[0] /= N_WAVELENGTHS as f64;
+    }
+}
+
+impl Wavelengths for SampledWavelengths {
+    type Radiance = SampledSpectrum;
+    fn upsample(&self, color: Color) -> SampledSpectrum {
+        SampledSpectrum::from_rgb(color, self)
+    }
+    /// A dispersive material sends each wavelength its own way, so only the hero is followed.
+    fn scatter(
+        &mut self,
+        material: &AnyMaterial,
+        r_in: &Ray,
+        rec: &HitRecord,
+    ) -> Option<ScatterRecord> {
+        if material.is_dispersive() {
+            self.terminate_secondary();
+            material.scatter_wavelength(r_in, rec, self.hero())
+        } else {
+            material.scatter(r_in, rec)
+        }
+    }
+}
+
+/// Radiance or reflectance at each wavelength of a [`SampledWavelengths`].
+#[derive(Clone, Copy, Default)]
+pub struct SampledSpectrum([f64; N_WAVELENGTHS]);
+
+impl SampledSpectrum {
+    /// Upsamples a linear RGB color to a smooth spectrum.
+    ///
+    /// Each channel gets a band of the visible range with soft edges. The bands sum to one
+    /// everywhere, so white stays flat and reflectances in [0, 1] stay in [0, 1].
+    pub fn from_rgb(color: Color, lambda: &SampledWavelengths) -> Self {
+        let Vec3(r, g, b) = color;
+        Self(lambda.lambda.map(|l| {
+            let red = smoothstep(l - 590.0);
+            let blue = 1.0 - smoothstep(l - 490.0);
+            let green = 1.0 - red - blue;
+            r * red + g * green + b * blue
+        }))
+    }
+
+    /// Converts the spectrum to linear sRGB through the CIE color matching functions.
+    ///
+    /// The result is white balanced so that a flat spectrum of 1 becomes `Color::splat(1.0)`,
+    /// just like white is in RGB mode.
+    pub fn to_rgb(self, lambda: &SampledWavelengths) -> Color {
+        let mut xyz = Color::default();
+        for ((value, l), pdf) in self.0.into_iter().zip(lambda.lambda).zip(lambda.pdf) {
+            if pdf != 0.0 {
+                xyz += cie_xyz(l) * (value / pdf);
+            }
+        }
+        xyz_to_linear_srgb(xyz / N_WAVELENGTHS as f64) * *WHITE_BALANCE
+    }
+}
+
+impl Add for SampledSpectrum {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self(array::from_fn(|i| self.0[i] + other.0[i]))
+    }
+}
+
+impl AddAssign for SampledSpectrum {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Mul for SampledSpectrum {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Self(array::from_fn(|i| self.0[i] * other.0[i]))
+    }
+}
+
+impl Mul<f64> for SampledSpectrum {
+    type Output = Self;
+    fn mul(self, other: f64) -> Self {
+        Self(self.0.map(|v| v * other))
+    }
+}
+
+/// Logistic step with a width of about 20nm.
+fn smoothstep(x: f64) -> f64 {
+    1.0 / (1.0 + (-x / 5.0).exp())
+}
+
+/// Per channel scale that turns the sRGB of a flat spectrum into white.
+static WHITE_BALANCE: LazyLock<Color> = LazyLock::new(|| {
+    let white: Color = (LAMBDA_MIN as u64..LAMBDA_MAX as u64)
+        .map(|l| cie_xyz(l as f64 + 0.5))
+        .sum();
+    let Vec3(r, g, b) = xyz_to_linear_srgb(white);
+    Color::new(r.recip(), g.recip(), b.recip())
+});
+
+/// Spectral radiance of a black body at `kelvin`, normalized so that its peak is 1.
+pub fn blackbody(wavelength: f64, kelvin: f64) -> f64 {
+    // Planck's law, up to the constant factor that the normalization cancels out
+    let planck = |wavelength_nm: f64| {
+        const C2: f64 = 1.438_776_877e-2; // hc/k in m·K
+        let l = wavelength_nm * 1e-9;
+        1.0 / (l.powi(5) * ((C2 / (l * kelvin)).exp() - 1.0))
+    };
+    // Wien's displacement law gives the peak
+    let peak = 2.897_771_955e-3 / kelvin * 1e9;
+    planck(wavelength) / planck(peak)
+}
+
+/// The linear sRGB color of a black body at `kelvin`, scaled to a luminance of 1.
+///
+/// 6500K is close to white, lower temperatures are warmer (2700K is a tungsten bulb) and
+/// higher ones are bluer.
+pub fn blackbody_color(kelvin: f64) -> Color {
+    let xyz = spectrum_to_xyz(|lambda| blackbody(lambda, kelvin));
+    let Vec3(r, g, b) = xyz_to_linear_srgb(xyz / xyz.1);
+    Color::new(r.max(0.0), g.max(0.0), b.max(0.0))
+}
+
+/// The XYZ color of a spectrum, given as a function of the wavelength in nanometers.
+pub fn spectrum_to_xyz(spectrum: impl Fn(f64) -> f64) -> Color {
+    // the CIE tables go from 360nm to 830nm
+    (360..830)
+        .map(|l| l as f64 + 0.5)
+        .map(|l| cie_xyz(l) * spectrum(l))
+        .sum()
+}
+
+/// The CIE 1931 standard observer, using the multi-lobe Gaussian fit from Wyman, Sloan and
+/// Shirley, "Simple Analytic Approximations to the CIE XYZ Color Matching Functions" (2013).
+/// The components of the returned color are X, Y and Z.
+pub fn cie_xyz(lambda: f64) -> Color {
+    let g = |mu: f64, sigma_lo: f64, sigma_hi: f64| {
+        let t = (lambda - mu) / if lambda < mu { sigma_lo } else { sigma_hi };
+        (-0.5 * t * t).exp()
+    };
+    let x = 1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7)
+        - 0.065 * g(501.1, 20.4, 26.2);
+    let y = 0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1);
+    let z = 1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8);
+    Color::new(x, y, z)
+}
+
+#[rustfmt::skip]
+pub fn xyz_to_linear_srgb(Vec3(x, y, z): Color) -> Color {
+    Color::new(
+         3.2404542 * x - 1.5371385 * y - 0.4985314 * z,
+        -0.9692660 * x + 1.8760108 * y + 0.0415560 * z,
+         0.0556434 * x - 0.2040259 * y + 1.0572252 * z,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The xy chromaticity of the color that `blackbody_color` gives for `kelvin`.
+    #[rustfmt::skip]
+    fn chromaticity(kelvin: f64) -> (f64, f64) {
+        let Vec3(r, g, b) = blackbody_color(kelvin);
+        let x = 0.4124564 * r + 0.3575761 * g + 0.1804375 * b;
+        let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+        let z = 0.0193339 * r + 0.1191920 * g + 0.9503041 * b;
+        (x / (x + y + z), y / (x + y + z))
+    }
+
+    fn assert_chromaticity(kelvin: f64, expected: (f64, f64)) {
+        let (x, y) = chromaticity(kelvin);
+        assert!(
+            (x - expected.0).abs() < 0.002 && (y - expected.1).abs() < 0.002,
+            "{kelvin}K has chromaticity ({x:.4}, {y:.4}), expected {expected:?}",
+        );
+    }
+
+    #[test]
+    fn illuminant_a() {
+        assert_chromaticity(2856.0, (0.4476, 0.4074));
+    }
+
+    #[test]
+    fn daylight() {
+        assert_chromaticity(6500.0, (0.3135, 0.3236));
+    }
+
+    #[test]
+    fn luminance_is_one() {
+        for kelvin in [1900.0, 2700.0, 6500.0, 10000.0] {
+            let y = blackbody_color(kelvin).luminance();
+            assert!((y - 1.0).abs() < 0.01, "{kelvin}K has luminance {y}");
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::array;
+use std::path::Path;
+
+use image::Rgba32FImage;
+
+use crate::color::Color;
+use crate::interval::Interval;
+use crate::vec3::Point;
+
+pub trait Texture: Send + Sync {
+    fn value(&self, u: f64, v: f64, p: Point) -> Color;
+    /// How opaque the surface is, from 0 (fully cut out) to 1.
+    #[expect(unused_variables)]
+    fn alpha(&self, u: f64, v: f64, p: Point) -> f64 {
+        1.0
+    }
+    /// The color averaged over a surface, for how much light an emitter with this texture gives
+    /// off. By default this averages over the uv square, so textures that depend on the position
+    /// instead must override it.
+    fn average(&self) -> Color {
+        const N: usize = 16;
+        let sum: Color = (0..N * N)
+            .map(|i| {
+                let u = ((i % N) as f64 + 0.5) / N as f64;
+                let v = ((i / N) as f64 + 0.5) / N as f64;
+                self.value(u, v, Point::default())
+            })
+            .sum();
+        sum / (N * N) as f64
+    }
+}
+
+impl Texture for Color {
+    fn value(&self, _: f64, _: f64, _: Point) -> Color {
+        *self
+    }
+    fn average(&self) -> Color {
+        *self
+    }
+}
+
+/// A 3D checkerboard, so that it works on any object regardless of uv coordinates.
+#[derive(Clone, Copy)]
+pub struct CheckerTexture {
+    inv_scale: f64,
+    even: Color,
+    odd: Color,
+}
+
+impl CheckerTexture {
+    pub fn new(scale: f64, even: impl Into<Color>, odd: impl Into<Color>) -> Self {
+        Self {
+            inv_scale: scale.recip(),
+            even: even.into(),
+            odd: odd.into(),
+        }
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, _: f64, _: f64, p: Point) -> Color {
+        let sum: i64 = p.into_iter().map(|x| (self.inv_scale * x).floor() as i64).sum();
+        if sum % 2 == 0 {
+            self.even
+        } else {
+            self.odd
+        }
+    }
+    /// Half of any large surface is covered by each color.
+    fn average(&self) -> Color {
+        (self.even + self.odd) / 2.0
+    }
+}
+
+/// A texture with no color that only cuts out parts of a surface, for use with
+/// [`AlphaMask`](crate::object::AlphaMask).
+pub struct ProceduralMask<F>(pub F);
+
+impl<F: Fn(f64, f64, Point) -> f64 + Send + Sync> Texture for ProceduralMask<F> {
+    fn value(&self, _: f64, _: f64, _: Point) -> Color {
+        Color::splat(1.0)
+    }
+    fn alpha(&self, u: f64, v: f64, p: Point) -> f64 {
+        (self.0)(u, v, p)
+    }
+}
+
+/// An image mapped over the uv square, with `v = 0` at the bottom of the image. Lookups are
+/// bilinearly filtered.
+pub struct ImageTexture {
+    image: Rgba32FImage,
+}
+
+impl ImageTexture {
+    /// Loads an image with gamma encoded colors, like photos and albedo maps.
+    pub fn load(path: impl AsRef<Path>) -> color_eyre::Result<Self> {
+        let mut texture = Self::load_linear(path)?;
+        // undo what `Color::write_to_buf` does.
+        for pixel in texture.image.pixels_mut() {
+            for c in &mut pixel.0[..3] {
+                *c *= *c;
+            }
+        }
+        Ok(texture)
+    }
+
+    /// Loads an image whose values are used as is, like normal maps and height maps.
+    pub fn load_linear(path: impl AsRef<Path>) -> color_eyre::Result<Self> {
+        Ok(Self::new(image::open(path)?.into_rgba32f()))
+    }
+
+    /// Uses the values of `image` as is.
+    pub fn new(image: Rgba32FImage) -> Self {
+        Self { image }
+    }
+
+    fn pixel(&self, u: f64, v: f64) -> [f64; 4] {
+        let unit_interval = Interval::new(0.0, 1.0);
+        let (width, height) = self.image.dimensions();
+        let x = unit_interval.clamp(u) * width as f64 - 0.5;
+        let y = (1.0 - unit_interval.clamp(v)) * height as f64 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+
+        let texel = |x: f64, y: f64| {
+            let i = (x.max(0.0) as u32).min(width - 1);
+            let j = (y.max(0.0) as u32).min(height - 1);
+            self.image.get_pixel(i, j).0.map(f64::from)
+        };
+        let [a, b, c, d] = [
+            texel(x0, y0),
+            texel(x0 + 1.0, y0),
+            texel(x0, y0 + 1.0),
+            texel(x0 + 1.0, y0 + 1.0),
+        ];
+        array::from_fn(|k| {
+            let top = a[k] + fx * (b[k] - a[k]);
+            let bottom = c[k] + fx * (d[k] - c[k]);
+            top + fy * (bottom - top)
+        })
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _: Point) -> Color {
+        let [r, g, b, _] = self.pixel(u, v);
+        Color::new(r, g, b)
+    }
+    fn alpha(&self, u: f64, v: f64, _: Point) -> f64 {
+        self.pixel(u, v)[3]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_sampling() {
+        // black and white on the bottom row, red and blue on the top row
+        let pixels = [[0.0, 0.0, 0.0], [1.0, 1.0, 1.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]];
+        let image = Rgba32FImage::from_fn(2, 2, |i, j| {
+            let [r, g, b] = pixels[(2 * (1 - j) + i) as usize];
+            image::Rgba([r, g, b, 1.0])
+        });
+        let texture = ImageTexture::new(image);
+        let value = |u, v| {
+            let color = texture.value(u, v, Point::default());
+            [color.0, color.1, color.2]
+        };
+
+        // pixel centers
+        assert_eq!(value(0.25, 0.25), [0.0, 0.0, 0.0]);
+        assert_eq!(value(0.75, 0.25), [1.0, 1.0, 1.0]);
+        assert_eq!(value(0.25, 0.75), [1.0, 0.0, 0.0]);
+        assert_eq!(value(0.75, 0.75), [0.0, 0.0, 1.0]);
+        // halfway between them, and clamped at the edges
+        assert_eq!(value(0.5, 0.25), [0.5, 0.5, 0.5]);
+        assert_eq!(value(0.25, 0.5), [0.5, 0.0, 0.0]);
+        assert_eq!(value(0.5, 0.5), [0.5, 0.25, 0.5]);
+        assert_eq!(value(0.0, 1.0), [1.0, 0.0, 0.0]);
+        assert_eq!(texture.alpha(0.5, 0.5, Point::default()), 1.0);
+    }
+}
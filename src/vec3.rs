@@ -82,7 +82,7 @@ impl<Token: Vec3Token<Data = f64>> Vec3<Token> {
 
     pub fn random_in_unit_disk() -> Self {
         loop {
-            let p = Vec3(random_double(), random_double(), 0.0);
+            let p = Vec3(random_double_in(-1.0, 1.0), random_double_in(-1.0, 1.0), 0.0);
             if p.length_squared() < 1.0 {
                 return p;
             }